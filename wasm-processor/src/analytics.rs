@@ -4,6 +4,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// Above this many records, computing percentiles by fully sorting the rank
+// vector gets wasteful; switch to a streaming quantile sketch instead.
+const EXACT_QUANTILE_THRESHOLD: usize = 10_000;
+const QUANTILES: RankPercentiles = RankPercentiles { p10: 0.10, p25: 0.25, p75: 0.75, p90: 0.90, p95: 0.95 };
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalyticsResult {
     pub total_records: u64,
@@ -11,6 +16,16 @@ pub struct AnalyticsResult {
     pub average_closing_rank: f64,
     pub median_opening_rank: f64,
     pub median_closing_rank: f64,
+    pub p10_opening_rank: f64,
+    pub p25_opening_rank: f64,
+    pub p75_opening_rank: f64,
+    pub p90_opening_rank: f64,
+    pub p95_opening_rank: f64,
+    pub p10_closing_rank: f64,
+    pub p25_closing_rank: f64,
+    pub p75_closing_rank: f64,
+    pub p90_closing_rank: f64,
+    pub p95_closing_rank: f64,
     pub min_opening_rank: u32,
     pub max_closing_rank: u32,
     pub rank_distribution: HashMap<String, u64>,
@@ -23,6 +38,11 @@ pub struct AnalyticsResult {
     pub counselling_body_distribution: HashMap<String, u64>,
     pub level_distribution: HashMap<String, u64>,
     pub stream_distribution: HashMap<String, u64>,
+    /// Median closing rank within each state, so a rank comparison doesn't
+    /// require pulling the raw per-record data back out of `rank_distribution`.
+    pub state_median_closing_rank: HashMap<String, f64>,
+    /// Median closing rank within each category (e.g. General/OBC/SC/ST).
+    pub category_median_closing_rank: HashMap<String, f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +63,182 @@ pub struct CutoffRecord {
     pub stream: String,
 }
 
+struct RankPercentiles {
+    p10: f64,
+    p25: f64,
+    p75: f64,
+    p90: f64,
+    p95: f64,
+}
+
+/// Median plus the configured percentiles of a rank column, computed either
+/// exactly (small inputs) or from a streaming sketch (large inputs).
+struct RankQuantiles {
+    median: f64,
+    p10: f64,
+    p25: f64,
+    p75: f64,
+    p90: f64,
+    p95: f64,
+}
+
+impl RankQuantiles {
+    /// Exact quantiles from a vector already sorted ascending.
+    fn from_sorted(sorted: &[u32]) -> Self {
+        Self {
+            median: median_of_sorted(sorted),
+            p10: percentile_of_sorted(sorted, QUANTILES.p10),
+            p25: percentile_of_sorted(sorted, QUANTILES.p25),
+            p75: percentile_of_sorted(sorted, QUANTILES.p75),
+            p90: percentile_of_sorted(sorted, QUANTILES.p90),
+            p95: percentile_of_sorted(sorted, QUANTILES.p95),
+        }
+    }
+
+    /// Approximate quantiles from a t-digest built over an unsorted stream.
+    fn from_digest(digest: &TDigest) -> Self {
+        Self {
+            median: digest.quantile(0.5),
+            p10: digest.quantile(QUANTILES.p10),
+            p25: digest.quantile(QUANTILES.p25),
+            p75: digest.quantile(QUANTILES.p75),
+            p90: digest.quantile(QUANTILES.p90),
+            p95: digest.quantile(QUANTILES.p95),
+        }
+    }
+}
+
+/// Nearest-rank percentile (`p` in `[0.0, 1.0]`) of an ascending-sorted slice.
+fn percentile_of_sorted(sorted: &[u32], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx.min(sorted.len() - 1)] as f64
+}
+
+/// Median of an ascending-sorted slice, averaging the two middle values for
+/// even-length inputs -- the same convention the old median calculation used.
+fn median_of_sorted(sorted: &[u32]) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let n = sorted.len();
+    if n % 2 == 0 {
+        (sorted[n / 2 - 1] as f64 + sorted[n / 2] as f64) / 2.0
+    } else {
+        sorted[n / 2] as f64
+    }
+}
+
+/// A centroid: a cluster of merged samples, tracked by its running mean and
+/// the number of samples folded into it.
+#[derive(Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Streaming quantile sketch (t-digest style): incoming values are merged
+/// into the nearest of a bounded set of centroids instead of being kept
+/// individually, so memory stays O(max_centroids) regardless of stream
+/// length. Quantiles are interpolated from cumulative centroid weight.
+/// This is a simplified digest (uniform per-centroid capacity, no
+/// size-limit function biasing resolution toward the tails) rather than a
+/// full Ted Dunning t-digest, which keeps it easy to hand-verify while
+/// staying within a few percent of the exact value in practice.
+struct TDigest {
+    centroids: Vec<Centroid>,
+    max_centroids: usize,
+    count: f64,
+}
+
+impl TDigest {
+    fn new(max_centroids: usize) -> Self {
+        Self { centroids: Vec::with_capacity(max_centroids), max_centroids, count: 0.0 }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1.0;
+
+        if self.centroids.len() < self.max_centroids {
+            let pos = self.centroids.partition_point(|c| c.mean < value);
+            self.centroids.insert(pos, Centroid { mean: value, weight: 1.0 });
+            return;
+        }
+
+        let nearest = self
+            .centroids
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (a.mean - value).abs().partial_cmp(&(b.mean - value).abs()).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(i, _)| i)
+            .unwrap();
+
+        let c = &mut self.centroids[nearest];
+        c.weight += 1.0;
+        c.mean += (value - c.mean) / c.weight;
+
+        // The merged centroid's mean may have shifted past a neighbor;
+        // re-sort since the vector is small (bounded by max_centroids).
+        self.centroids.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    /// `q` is in `[0.0, 1.0]`. Interpolates linearly between the cumulative
+    /// weight "centers" of adjacent centroids rather than snapping to the
+    /// nearest one, so the result isn't quantized to at most
+    /// `max_centroids` discrete values.
+    fn quantile(&self, q: f64) -> f64 {
+        if self.centroids.is_empty() {
+            return 0.0;
+        }
+        if self.centroids.len() == 1 {
+            return self.centroids[0].mean;
+        }
+
+        let target = q * self.count;
+        let mut cumulative = 0.0;
+        let mut prev_center = 0.0;
+        let mut prev_mean = self.centroids[0].mean;
+
+        for (i, c) in self.centroids.iter().enumerate() {
+            let center = cumulative + c.weight / 2.0;
+            if target <= center {
+                if i == 0 {
+                    return c.mean;
+                }
+                let frac = (target - prev_center) / (center - prev_center);
+                return prev_mean + frac * (c.mean - prev_mean);
+            }
+            cumulative += c.weight;
+            prev_center = center;
+            prev_mean = c.mean;
+        }
+
+        self.centroids.last().unwrap().mean
+    }
+}
+
+const TDIGEST_MAX_CENTROIDS: usize = 100;
+
+/// Median of an unsorted group of ranks (sorted here), for the
+/// per-state/category summaries. Groups are assumed small enough that sorting them exactly is
+/// cheap even when the overall dataset went through the sketch path.
+fn group_median(mut ranks: Vec<u32>) -> f64 {
+    if ranks.is_empty() {
+        return 0.0;
+    }
+    ranks.sort_unstable();
+    let n = ranks.len();
+    if n % 2 == 0 {
+        (ranks[n / 2 - 1] + ranks[n / 2]) as f64 / 2.0
+    } else {
+        ranks[n / 2] as f64
+    }
+}
+
 pub struct AnalyticsProcessor {
     // No persistent state needed for analytics
 }
@@ -55,7 +251,7 @@ impl AnalyticsProcessor {
     pub fn calculate_analytics(&self, data_json: &str) -> Result<String, Box<dyn std::error::Error>> {
         // Parse JSON data
         let records: Vec<CutoffRecord> = serde_json::from_str(data_json)?;
-        
+
         if records.is_empty() {
             return Ok(serde_json::to_string(&AnalyticsResult {
                 total_records: 0,
@@ -63,6 +259,16 @@ impl AnalyticsProcessor {
                 average_closing_rank: 0.0,
                 median_opening_rank: 0.0,
                 median_closing_rank: 0.0,
+                p10_opening_rank: 0.0,
+                p25_opening_rank: 0.0,
+                p75_opening_rank: 0.0,
+                p90_opening_rank: 0.0,
+                p95_opening_rank: 0.0,
+                p10_closing_rank: 0.0,
+                p25_closing_rank: 0.0,
+                p75_closing_rank: 0.0,
+                p90_closing_rank: 0.0,
+                p95_closing_rank: 0.0,
                 min_opening_rank: 0,
                 max_closing_rank: 0,
                 rank_distribution: HashMap::new(),
@@ -75,37 +281,37 @@ impl AnalyticsProcessor {
                 counselling_body_distribution: HashMap::new(),
                 level_distribution: HashMap::new(),
                 stream_distribution: HashMap::new(),
+                state_median_closing_rank: HashMap::new(),
+                category_median_closing_rank: HashMap::new(),
             })?);
         }
-        
+
         // Calculate basic statistics
         let total_records = records.len() as u64;
-        
-        // Calculate rank statistics
-        let mut opening_ranks: Vec<u32> = records.iter().map(|r| r.opening_rank).collect();
-        let mut closing_ranks: Vec<u32> = records.iter().map(|r| r.closing_rank).collect();
-        
-        opening_ranks.sort();
-        closing_ranks.sort();
-        
-        let average_opening_rank = opening_ranks.iter().sum::<u32>() as f64 / total_records as f64;
-        let average_closing_rank = closing_ranks.iter().sum::<u32>() as f64 / total_records as f64;
-        
-        let median_opening_rank = if total_records % 2 == 0 {
-            (opening_ranks[total_records as usize / 2 - 1] + opening_ranks[total_records as usize / 2]) as f64 / 2.0
-        } else {
-            opening_ranks[total_records as usize / 2] as f64
-        };
-        
-        let median_closing_rank = if total_records % 2 == 0 {
-            (closing_ranks[total_records as usize / 2 - 1] + closing_ranks[total_records as usize / 2]) as f64 / 2.0
+
+        let min_opening_rank = records.iter().map(|r| r.opening_rank).min().unwrap_or(0);
+        let max_closing_rank = records.iter().map(|r| r.closing_rank).max().unwrap_or(0);
+        let average_opening_rank =
+            records.iter().map(|r| r.opening_rank as u64).sum::<u64>() as f64 / total_records as f64;
+        let average_closing_rank =
+            records.iter().map(|r| r.closing_rank as u64).sum::<u64>() as f64 / total_records as f64;
+
+        let (opening_quantiles, closing_quantiles) = if records.len() <= EXACT_QUANTILE_THRESHOLD {
+            let mut opening_ranks: Vec<u32> = records.iter().map(|r| r.opening_rank).collect();
+            let mut closing_ranks: Vec<u32> = records.iter().map(|r| r.closing_rank).collect();
+            opening_ranks.sort_unstable();
+            closing_ranks.sort_unstable();
+            (RankQuantiles::from_sorted(&opening_ranks), RankQuantiles::from_sorted(&closing_ranks))
         } else {
-            closing_ranks[total_records as usize / 2] as f64
+            let mut opening_digest = TDigest::new(TDIGEST_MAX_CENTROIDS);
+            let mut closing_digest = TDigest::new(TDIGEST_MAX_CENTROIDS);
+            for record in &records {
+                opening_digest.add(record.opening_rank as f64);
+                closing_digest.add(record.closing_rank as f64);
+            }
+            (RankQuantiles::from_digest(&opening_digest), RankQuantiles::from_digest(&closing_digest))
         };
-        
-        let min_opening_rank = *opening_ranks.first().unwrap_or(&0);
-        let max_closing_rank = *closing_ranks.last().unwrap_or(&0);
-        
+
         // Calculate distributions
         let mut rank_distribution = HashMap::new();
         let mut state_distribution = HashMap::new();
@@ -117,46 +323,70 @@ impl AnalyticsProcessor {
         let mut counselling_body_distribution = HashMap::new();
         let mut level_distribution = HashMap::new();
         let mut stream_distribution = HashMap::new();
-        
+        let mut state_closing_ranks: HashMap<String, Vec<u32>> = HashMap::new();
+        let mut category_closing_ranks: HashMap<String, Vec<u32>> = HashMap::new();
+
         for record in &records {
             // Rank distribution (grouped by ranges)
             let rank_range = self.get_rank_range(record.opening_rank);
             *rank_distribution.entry(rank_range).or_insert(0) += 1;
-            
+
             // State distribution
             *state_distribution.entry(record.state.clone()).or_insert(0) += 1;
-            
+
             // Course distribution
             *course_distribution.entry(record.course_name.clone()).or_insert(0) += 1;
-            
+
             // College distribution
             *college_distribution.entry(record.college_name.clone()).or_insert(0) += 1;
-            
+
             // Year distribution
             *year_distribution.entry(record.year.to_string()).or_insert(0) += 1;
-            
+
             // Round distribution
             *round_distribution.entry(record.round.to_string()).or_insert(0) += 1;
-            
+
             // Category distribution
             *category_distribution.entry(record.category.clone()).or_insert(0) += 1;
-            
+
             // Counselling body distribution
             *counselling_body_distribution.entry(record.counselling_body.clone()).or_insert(0) += 1;
-            
+
             // Level distribution
             *level_distribution.entry(record.level.clone()).or_insert(0) += 1;
-            
+
             // Stream distribution
             *stream_distribution.entry(record.stream.clone()).or_insert(0) += 1;
+
+            state_closing_ranks.entry(record.state.clone()).or_default().push(record.closing_rank);
+            category_closing_ranks.entry(record.category.clone()).or_default().push(record.closing_rank);
         }
-        
+
+        let state_median_closing_rank = state_closing_ranks
+            .into_iter()
+            .map(|(state, ranks)| (state, group_median(ranks)))
+            .collect();
+        let category_median_closing_rank = category_closing_ranks
+            .into_iter()
+            .map(|(category, ranks)| (category, group_median(ranks)))
+            .collect();
+
         let result = AnalyticsResult {
             total_records,
             average_opening_rank,
             average_closing_rank,
-            median_opening_rank,
-            median_closing_rank,
+            median_opening_rank: opening_quantiles.median,
+            median_closing_rank: closing_quantiles.median,
+            p10_opening_rank: opening_quantiles.p10,
+            p25_opening_rank: opening_quantiles.p25,
+            p75_opening_rank: opening_quantiles.p75,
+            p90_opening_rank: opening_quantiles.p90,
+            p95_opening_rank: opening_quantiles.p95,
+            p10_closing_rank: closing_quantiles.p10,
+            p25_closing_rank: closing_quantiles.p25,
+            p75_closing_rank: closing_quantiles.p75,
+            p90_closing_rank: closing_quantiles.p90,
+            p95_closing_rank: closing_quantiles.p95,
             min_opening_rank,
             max_closing_rank,
             rank_distribution,
@@ -169,12 +399,14 @@ impl AnalyticsProcessor {
             counselling_body_distribution,
             level_distribution,
             stream_distribution,
+            state_median_closing_rank,
+            category_median_closing_rank,
         };
-        
+
         serde_json::to_string(&result)
             .map_err(|e| format!("Serialization error: {}", e).into())
     }
-    
+
     fn get_rank_range(&self, rank: u32) -> String {
         match rank {
             1..=100 => "1-100".to_string(),
@@ -6,6 +6,21 @@ use lz4_flex::{compress_prepend_size, decompress_size_prepended};
 // use zstd::{encode_all, decode_all}; // Disabled for now due to native dependencies
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::deflate::{self, DeflateMode};
+use crate::stats::{IntervalCounts, IntervalReporter, LatencyRing, RunningAverage};
+
+// Content-defined chunking thresholds (FastCDC normalized chunking, level ~2)
+const CDC_MIN_CHUNK_SIZE: usize = 2 * 1024;
+const CDC_AVG_CHUNK_SIZE: usize = 8 * 1024;
+const CDC_MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+// Bound on how many recent latency samples are kept for percentile
+// estimates; the running averages themselves are O(1) regardless.
+const LATENCY_RING_CAPACITY: usize = 256;
+const DEFAULT_REPORT_INTERVAL_MS: u64 = 10_000;
 
 // Helper for logging from Rust to browser console
 #[wasm_bindgen]
@@ -27,105 +42,306 @@ pub struct CompressionStats {
     pub average_compression_ratio: f64,
     pub average_compression_time: f64,
     pub average_decompression_time: f64,
+    pub total_chunked_compressions: u64,
+    pub total_chunks_processed: u64,
+    pub total_unique_chunks: u64,
+    pub average_dedup_ratio: f64,
+    pub total_lz4_compressions: u64,
+    pub average_lz4_compression_ratio: f64,
+    pub total_deflate_compressions: u64,
+    pub average_deflate_compression_ratio: f64,
+}
+
+/// One content-defined chunk's place in a `ChunkManifest`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkManifestEntry {
+    pub hash: u64,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Describes how a blob was split into deduplicated chunks so it can be
+/// reassembled with `CompressionProcessor::decompress_chunked`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    pub chunks: Vec<ChunkManifestEntry>,
+    pub unique_chunk_count: usize,
+    pub total_chunk_count: usize,
+}
+
+/// Atomic-counter-backed stats storage. `CompressionStats` is just a
+/// point-in-time snapshot of this built by `get_stats`; the fields here are
+/// what actually gets touched on every call, and every field is safe to
+/// update from a shared `&self`.
+struct AtomicCompressionStats {
+    total_compressions: AtomicU64,
+    total_decompressions: AtomicU64,
+    total_bytes_compressed: AtomicU64,
+    total_bytes_decompressed: AtomicU64,
+    compression_ratio: RunningAverage,
+    compression_time: RunningAverage,
+    decompression_time: RunningAverage,
+    total_chunked_compressions: AtomicU64,
+    total_chunks_processed: AtomicU64,
+    total_unique_chunks: AtomicU64,
+    dedup_ratio: RunningAverage,
+    total_lz4_compressions: AtomicU64,
+    lz4_ratio: RunningAverage,
+    total_deflate_compressions: AtomicU64,
+    deflate_ratio: RunningAverage,
+}
+
+impl AtomicCompressionStats {
+    fn new() -> Self {
+        Self {
+            total_compressions: AtomicU64::new(0),
+            total_decompressions: AtomicU64::new(0),
+            total_bytes_compressed: AtomicU64::new(0),
+            total_bytes_decompressed: AtomicU64::new(0),
+            compression_ratio: RunningAverage::new(),
+            compression_time: RunningAverage::new(),
+            decompression_time: RunningAverage::new(),
+            total_chunked_compressions: AtomicU64::new(0),
+            total_chunks_processed: AtomicU64::new(0),
+            total_unique_chunks: AtomicU64::new(0),
+            dedup_ratio: RunningAverage::new(),
+            total_lz4_compressions: AtomicU64::new(0),
+            lz4_ratio: RunningAverage::new(),
+            total_deflate_compressions: AtomicU64::new(0),
+            deflate_ratio: RunningAverage::new(),
+        }
+    }
+
+    fn snapshot(&self) -> CompressionStats {
+        CompressionStats {
+            total_compressions: self.total_compressions.load(Ordering::Relaxed),
+            total_decompressions: self.total_decompressions.load(Ordering::Relaxed),
+            total_bytes_compressed: self.total_bytes_compressed.load(Ordering::Relaxed),
+            total_bytes_decompressed: self.total_bytes_decompressed.load(Ordering::Relaxed),
+            average_compression_ratio: self.compression_ratio.average(),
+            average_compression_time: self.compression_time.average(),
+            average_decompression_time: self.decompression_time.average(),
+            total_chunked_compressions: self.total_chunked_compressions.load(Ordering::Relaxed),
+            total_chunks_processed: self.total_chunks_processed.load(Ordering::Relaxed),
+            total_unique_chunks: self.total_unique_chunks.load(Ordering::Relaxed),
+            average_dedup_ratio: self.dedup_ratio.average(),
+            total_lz4_compressions: self.total_lz4_compressions.load(Ordering::Relaxed),
+            average_lz4_compression_ratio: self.lz4_ratio.average(),
+            total_deflate_compressions: self.total_deflate_compressions.load(Ordering::Relaxed),
+            average_deflate_compression_ratio: self.deflate_ratio.average(),
+        }
+    }
 }
 
 pub struct CompressionProcessor {
-    stats: CompressionStats,
-    compression_times: Vec<f64>,
-    decompression_times: Vec<f64>,
+    stats: AtomicCompressionStats,
+    compression_latency: LatencyRing,
+    decompression_latency: LatencyRing,
+    reporter: IntervalReporter,
+    // Compressed, deduplicated chunks keyed by content hash. Shared across
+    // calls so re-uploaded datasets that overlap with earlier ones reuse
+    // chunks instead of storing them again. Lives independently of `stats`
+    // (see `clear_stats`): it's never evicted and grows unbounded for the
+    // life of the processor.
+    chunk_store: Mutex<HashMap<u64, Vec<u8>>>,
 }
 
 impl CompressionProcessor {
     pub fn new() -> Self {
         Self {
-            stats: CompressionStats {
-                total_compressions: 0,
-                total_decompressions: 0,
-                total_bytes_compressed: 0,
-                total_bytes_decompressed: 0,
-                average_compression_ratio: 0.0,
-                average_compression_time: 0.0,
-                average_decompression_time: 0.0,
-            },
-            compression_times: Vec::new(),
-            decompression_times: Vec::new(),
+            stats: AtomicCompressionStats::new(),
+            compression_latency: LatencyRing::new(LATENCY_RING_CAPACITY),
+            decompression_latency: LatencyRing::new(LATENCY_RING_CAPACITY),
+            reporter: IntervalReporter::new(DEFAULT_REPORT_INTERVAL_MS),
+            chunk_store: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn compress_lz4(&mut self, data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    pub fn compress_lz4(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
-        
+
         let compressed = compress_prepend_size(data);
         let compression_time = start_time.elapsed().as_secs_f64() * 1000.0; // Convert to milliseconds
-        
-        // Update statistics
-        self.stats.total_compressions += 1;
-        self.stats.total_bytes_compressed += data.len() as u64;
-        self.compression_times.push(compression_time);
-        
+
+        self.stats.total_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_bytes_compressed.fetch_add(data.len() as u64, Ordering::Relaxed);
+        self.compression_latency.record(compression_time);
+        self.stats.compression_time.record(compression_time);
+
         let compression_ratio = ((data.len() - compressed.len()) as f64 / data.len() as f64) * 100.0;
-        self.update_compression_ratio(compression_ratio);
-        self.update_average_compression_time();
-        
+        self.stats.compression_ratio.record(compression_ratio);
+        self.stats.total_lz4_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.lz4_ratio.record(compression_ratio);
+
         Ok(compressed)
     }
 
-    pub fn decompress_lz4(&mut self, compressed_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    pub fn decompress_lz4(&self, compressed_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
-        
+
         let decompressed = decompress_size_prepended(compressed_data)?;
         let decompression_time = start_time.elapsed().as_secs_f64() * 1000.0; // Convert to milliseconds
-        
-        // Update statistics
-        self.stats.total_decompressions += 1;
-        self.stats.total_bytes_decompressed += decompressed.len() as u64;
-        self.decompression_times.push(decompression_time);
-        self.update_average_decompression_time();
-        
+
+        self.stats.total_decompressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_bytes_decompressed.fetch_add(decompressed.len() as u64, Ordering::Relaxed);
+        self.decompression_latency.record(decompression_time);
+        self.stats.decompression_time.record(decompression_time);
+
         Ok(decompressed)
     }
 
     // ZSTD functions disabled for now due to native dependencies
-    // pub fn compress_zstd(&mut self, data: &[u8], level: i32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    // pub fn compress_zstd(&self, data: &[u8], level: i32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     //     // Implementation would go here
     // }
 
-    fn update_compression_ratio(&mut self, new_ratio: f64) {
-        let total_compressions = self.stats.total_compressions as f64;
-        self.stats.average_compression_ratio = 
-            (self.stats.average_compression_ratio * (total_compressions - 1.0) + new_ratio) / total_compressions;
+    /// Compress with the pure-Rust DEFLATE backend, wrapped in a zlib
+    /// header/trailer so browsers can interop with gzip/zlib content
+    /// without native bindings.
+    pub fn compress_deflate(&self, data: &[u8], mode: DeflateMode) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        let compressed = deflate::compress_zlib(data, mode);
+        let compression_time = start_time.elapsed().as_secs_f64() * 1000.0;
+
+        self.stats.total_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_bytes_compressed.fetch_add(data.len() as u64, Ordering::Relaxed);
+        self.compression_latency.record(compression_time);
+        self.stats.compression_time.record(compression_time);
+
+        let compression_ratio = ((data.len() - compressed.len()) as f64 / data.len() as f64) * 100.0;
+        self.stats.compression_ratio.record(compression_ratio);
+        self.stats.total_deflate_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.deflate_ratio.record(compression_ratio);
+
+        Ok(compressed)
+    }
+
+    pub fn decompress_deflate(&self, compressed_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        let decompressed = deflate::decompress_zlib(compressed_data)?;
+        let decompression_time = start_time.elapsed().as_secs_f64() * 1000.0;
+
+        self.stats.total_decompressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_bytes_decompressed.fetch_add(decompressed.len() as u64, Ordering::Relaxed);
+        self.decompression_latency.record(decompression_time);
+        self.stats.decompression_time.record(decompression_time);
+
+        Ok(decompressed)
     }
 
-    fn update_average_compression_time(&mut self) {
-        if !self.compression_times.is_empty() {
-            self.stats.average_compression_time = 
-                self.compression_times.iter().sum::<f64>() / self.compression_times.len() as f64;
+    /// Split `data` into content-defined chunks (FastCDC), LZ4-compress each
+    /// chunk that hasn't been seen before, and return a manifest of
+    /// chunk-hash references. Re-uploaded datasets that share chunks with
+    /// earlier calls only pay the storage cost once.
+    pub fn compress_chunked(&self, data: &[u8]) -> Result<ChunkManifest, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        let boundaries = fastcdc_chunk_boundaries(data);
+        let mut entries = Vec::with_capacity(boundaries.len());
+        let mut unique_count = 0usize;
+
+        {
+            let mut chunk_store = self.chunk_store.lock().unwrap();
+            for (start, end) in &boundaries {
+                let chunk = &data[*start..*end];
+                let hash = fnv1a_hash(chunk);
+                if !chunk_store.contains_key(&hash) {
+                    chunk_store.insert(hash, compress_prepend_size(chunk));
+                    unique_count += 1;
+                }
+                entries.push(ChunkManifestEntry {
+                    hash,
+                    offset: *start,
+                    length: end - start,
+                });
+            }
         }
+
+        let compression_time = start_time.elapsed().as_secs_f64() * 1000.0;
+
+        self.stats.total_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_bytes_compressed.fetch_add(data.len() as u64, Ordering::Relaxed);
+        self.compression_latency.record(compression_time);
+        self.stats.compression_time.record(compression_time);
+
+        let dedup_ratio = if entries.is_empty() {
+            0.0
+        } else {
+            (1.0 - unique_count as f64 / entries.len() as f64) * 100.0
+        };
+        self.stats.total_chunks_processed.fetch_add(entries.len() as u64, Ordering::Relaxed);
+        self.stats.total_unique_chunks.fetch_add(unique_count as u64, Ordering::Relaxed);
+        self.stats.total_chunked_compressions.fetch_add(1, Ordering::Relaxed);
+        self.stats.dedup_ratio.record(dedup_ratio);
+
+        Ok(ChunkManifest {
+            total_chunk_count: entries.len(),
+            unique_chunk_count: unique_count,
+            chunks: entries,
+        })
     }
 
-    fn update_average_decompression_time(&mut self) {
-        if !self.decompression_times.is_empty() {
-            self.stats.average_decompression_time = 
-                self.decompression_times.iter().sum::<f64>() / self.decompression_times.len() as f64;
+    /// Reassemble the original bytes from a `ChunkManifest`, looking each
+    /// chunk up in the dedup store.
+    pub fn decompress_chunked(&self, manifest: &ChunkManifest) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let chunk_store = self.chunk_store.lock().unwrap();
+        let mut output = Vec::new();
+        for entry in &manifest.chunks {
+            let compressed = chunk_store
+                .get(&entry.hash)
+                .ok_or_else(|| format!("missing chunk for hash {}", entry.hash))?;
+            output.extend_from_slice(&decompress_size_prepended(compressed)?);
         }
+        Ok(output)
     }
 
-    pub fn get_stats(&self) -> &CompressionStats {
-        &self.stats
+    pub fn get_stats(&self) -> CompressionStats {
+        self.stats.snapshot()
     }
 
-    pub fn clear_stats(&mut self) {
-        self.stats = CompressionStats {
-            total_compressions: 0,
-            total_decompressions: 0,
-            total_bytes_compressed: 0,
-            total_bytes_decompressed: 0,
-            average_compression_ratio: 0.0,
-            average_compression_time: 0.0,
-            average_decompression_time: 0.0,
+    /// If the reporting interval (default 10s) has elapsed, returns a JSON
+    /// snapshot of throughput, p50/p99 latency, and counts accumulated
+    /// since the previous report; otherwise `None`.
+    pub fn maybe_interval_report(&self) -> Option<String> {
+        let current = IntervalCounts {
+            operations: self.stats.total_compressions.load(Ordering::Relaxed)
+                + self.stats.total_decompressions.load(Ordering::Relaxed),
+            bytes: self.stats.total_bytes_compressed.load(Ordering::Relaxed)
+                + self.stats.total_bytes_decompressed.load(Ordering::Relaxed),
         };
-        self.compression_times.clear();
-        self.decompression_times.clear();
+        let (previous, elapsed_ms) = self.reporter.try_begin_emit(current)?;
+
+        let snapshot = serde_json::json!({
+            "processor": "compression",
+            "interval_ms": elapsed_ms,
+            "operations": current.operations.saturating_sub(previous.operations),
+            "bytes_processed": current.bytes.saturating_sub(previous.bytes),
+            "throughput_bytes_per_sec":
+                current.bytes.saturating_sub(previous.bytes) as f64 / (elapsed_ms / 1000.0),
+            "compression_p50_ms": self.compression_latency.percentile(0.5),
+            "compression_p99_ms": self.compression_latency.percentile(0.99),
+            "decompression_p50_ms": self.decompression_latency.percentile(0.5),
+            "decompression_p99_ms": self.decompression_latency.percentile(0.99),
+        });
+        // Start the next interval's percentiles from fresh samples.
+        self.compression_latency.clear();
+        self.decompression_latency.clear();
+        Some(snapshot.to_string())
+    }
+
+    /// Resets counters and latency samples only. `chunk_store` is left
+    /// alone: every `ChunkManifest` ever returned by `compress_chunked`
+    /// still references it by content hash, so clearing it here would
+    /// silently break `decompress_chunked` for any manifest a caller is
+    /// still holding. It has no eviction of its own and grows unbounded for
+    /// the life of the processor -- callers that need to reclaim that
+    /// memory must drop the whole `CompressionProcessor` and recreate it.
+    pub fn clear_stats(&mut self) {
+        self.stats = AtomicCompressionStats::new();
+        self.compression_latency.clear();
+        self.decompression_latency.clear();
     }
 }
 
@@ -135,4 +351,103 @@ impl CompressionProcessor {
 // #[wasm_bindgen]
 // pub fn compress_zstd(data: &[u8], compression_level: i32) -> Result<Vec<u8>, JsValue> {
 //     // Implementation would go here
-// }
\ No newline at end of file
+// }
+
+/// Gear hash table used by the FastCDC rolling hash. Generated once, on
+/// first use, from a fixed seed via splitmix64 so chunk boundaries are
+/// deterministic across runs without checking in 2KB of magic numbers.
+fn gear_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u64; 256];
+        let mut seed: u64 = 0x9E3779B97F4A7C15;
+        for slot in table.iter_mut() {
+            seed = splitmix64(seed);
+            *slot = seed;
+        }
+        table
+    })
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Deterministic bitmask with exactly `one_bits` bits set, used as the
+/// FastCDC normalized-chunking cut-point test. More one-bits makes a cut
+/// less likely (stricter), fewer makes it more likely (looser).
+fn cdc_mask(one_bits: u32) -> u64 {
+    let mut mask = 0u64;
+    let mut seed = splitmix64(0xC0FFEE ^ one_bits as u64);
+    let mut bits_set = 0;
+    while bits_set < one_bits {
+        seed = splitmix64(seed);
+        let bit = 1u64 << (seed % 64);
+        if mask & bit == 0 {
+            mask |= bit;
+            bits_set += 1;
+        }
+    }
+    mask
+}
+
+/// Split `data` into variable-length chunks using a Gear-hash rolling
+/// checksum with FastCDC normalized chunking: a stricter mask is used
+/// before the average target size is reached (discouraging tiny chunks),
+/// a looser mask after it (encouraging a cut before `max_size`), and a cut
+/// is forced at `max_size` if neither mask matches.
+fn fastcdc_chunk_boundaries(data: &[u8]) -> Vec<(usize, usize)> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let table = gear_table();
+    let avg_bits = (CDC_AVG_CHUNK_SIZE as u32).trailing_zeros();
+    let mask_s = cdc_mask(avg_bits + 2);
+    let mask_l = cdc_mask(avg_bits.saturating_sub(2).max(1));
+
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+
+    while start < data.len() {
+        let remaining = data.len() - start;
+        if remaining <= CDC_MIN_CHUNK_SIZE {
+            boundaries.push((start, data.len()));
+            break;
+        }
+
+        let max_len = remaining.min(CDC_MAX_CHUNK_SIZE);
+        let mut fp: u64 = 0;
+        let mut cut = max_len;
+        let mut i = CDC_MIN_CHUNK_SIZE.min(max_len);
+
+        while i < max_len {
+            let byte = data[start + i];
+            fp = (fp << 1).wrapping_add(table[byte as usize]);
+            let mask = if i < CDC_AVG_CHUNK_SIZE { mask_s } else { mask_l };
+            if fp & mask == 0 {
+                cut = i + 1;
+                break;
+            }
+            i += 1;
+        }
+
+        boundaries.push((start, start + cut));
+        start += cut;
+    }
+
+    boundaries
+}
+
+fn fnv1a_hash(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,204 @@
+// Field type-coercion layer used by `DataProcessor::process_cutoff_data` to
+// ingest messy source JSON (ranks shipped as `"1,234"`, blank fields, years
+// as floats) instead of hard-failing a strict `serde_json` deserialize.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// How to coerce one field's raw text into a `TypedValue`. `TimestampFmt`
+/// holds a strptime-style format string (e.g. `"%Y-%m-%d"`) for fields
+/// whose date layout isn't the default ISO form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    AsIs,
+    Integer,
+    Float,
+    Boolean,
+    Timestamp,
+    TimestampFmt(String),
+}
+
+impl FromStr for Conversion {
+    type Err = ConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "asis" | "string" | "text" => Ok(Conversion::AsIs),
+            "int" | "integer" => Ok(Conversion::Integer),
+            "float" | "double" | "number" => Ok(Conversion::Float),
+            "bool" | "boolean" => Ok(Conversion::Boolean),
+            "ts" | "timestamp" => Ok(Conversion::Timestamp),
+            // Anything else is treated as a strptime-style date format, so
+            // callers declare e.g. `"%d-%m-%Y"` directly as the conversion.
+            _ => Ok(Conversion::TimestampFmt(s.to_string())),
+        }
+    }
+}
+
+/// Result of coercing a raw field value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Text(String),
+    /// Unix timestamp, seconds since epoch, at UTC midnight for date-only values.
+    Timestamp(i64),
+}
+
+impl TypedValue {
+    /// View as a non-negative `u32`, for assembling rank/year/round fields.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            TypedValue::Integer(i) if *i >= 0 => u32::try_from(*i).ok(),
+            TypedValue::Float(f) if *f >= 0.0 => Some(*f as u32),
+            TypedValue::Timestamp(t) if *t >= 0 => u32::try_from(*t).ok(),
+            _ => None,
+        }
+    }
+
+    /// View as text, for assembling string fields regardless of how the
+    /// source JSON encoded the value.
+    pub fn as_text(&self) -> String {
+        match self {
+            TypedValue::Null => String::new(),
+            TypedValue::Integer(i) => i.to_string(),
+            TypedValue::Float(f) => f.to_string(),
+            TypedValue::Boolean(b) => b.to_string(),
+            TypedValue::Text(s) => s.clone(),
+            TypedValue::Timestamp(t) => t.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError(pub String);
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl Conversion {
+    /// Coerce `raw` per this conversion. Trims whitespace, strips comma
+    /// thousands separators before numeric parsing, and treats an
+    /// all-whitespace/empty string as null rather than an error.
+    pub fn convert(&self, raw: &str) -> Result<TypedValue, ConversionError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Ok(TypedValue::Null);
+        }
+
+        match self {
+            Conversion::AsIs => Ok(TypedValue::Text(trimmed.to_string())),
+            Conversion::Integer => {
+                let cleaned = strip_thousands_separators(trimmed);
+                cleaned
+                    .parse::<i64>()
+                    .or_else(|_| cleaned.parse::<f64>().map(|f| f.round() as i64))
+                    .map(TypedValue::Integer)
+                    .map_err(|_| ConversionError(format!("cannot parse '{}' as integer", raw)))
+            }
+            Conversion::Float => {
+                let cleaned = strip_thousands_separators(trimmed);
+                cleaned
+                    .parse::<f64>()
+                    .map(TypedValue::Float)
+                    .map_err(|_| ConversionError(format!("cannot parse '{}' as float", raw)))
+            }
+            Conversion::Boolean => match trimmed.to_lowercase().as_str() {
+                "true" | "1" | "yes" | "y" => Ok(TypedValue::Boolean(true)),
+                "false" | "0" | "no" | "n" => Ok(TypedValue::Boolean(false)),
+                _ => Err(ConversionError(format!("cannot parse '{}' as boolean", raw))),
+            },
+            Conversion::Timestamp => {
+                let cleaned = strip_thousands_separators(trimmed);
+                if let Ok(epoch) = cleaned.parse::<i64>() {
+                    Ok(TypedValue::Timestamp(epoch))
+                } else {
+                    parse_date(trimmed, "%Y-%m-%d").map(TypedValue::Timestamp)
+                }
+            }
+            Conversion::TimestampFmt(fmt) => parse_date(trimmed, fmt).map(TypedValue::Timestamp),
+        }
+    }
+}
+
+fn strip_thousands_separators(s: &str) -> String {
+    s.chars().filter(|c| *c != ',').collect()
+}
+
+/// Parse a date in `fmt` (supporting the `%Y`/`%m`/`%d` strptime tokens and
+/// literal separators) into a Unix timestamp at UTC midnight. No `chrono`
+/// dependency — days-since-epoch is computed with Howard Hinnant's
+/// civil-calendar algorithm.
+fn parse_date(raw: &str, fmt: &str) -> Result<i64, ConversionError> {
+    let mut year: Option<i64> = None;
+    let mut month: Option<u32> = None;
+    let mut day: Option<u32> = None;
+
+    let mut raw_chars = raw.chars().peekable();
+    let mut fmt_chars = fmt.chars().peekable();
+
+    while let Some(&fc) = fmt_chars.peek() {
+        if fc == '%' {
+            fmt_chars.next();
+            let spec = fmt_chars.next().ok_or_else(|| ConversionError(format!("invalid format string '{}'", fmt)))?;
+            let width = match spec {
+                'Y' => 4,
+                'm' | 'd' => 2,
+                other => return Err(ConversionError(format!("unsupported format token '%{}'", other))),
+            };
+
+            let mut digits = String::new();
+            for _ in 0..width {
+                match raw_chars.peek() {
+                    Some(c) if c.is_ascii_digit() => {
+                        digits.push(*c);
+                        raw_chars.next();
+                    }
+                    _ => break,
+                }
+            }
+            if digits.is_empty() {
+                return Err(ConversionError(format!("cannot parse '{}' with format '{}'", raw, fmt)));
+            }
+            let value: i64 = digits.parse().map_err(|_| ConversionError(format!("cannot parse '{}' with format '{}'", raw, fmt)))?;
+
+            match spec {
+                'Y' => year = Some(value),
+                'm' => month = Some(value as u32),
+                'd' => day = Some(value as u32),
+                _ => unreachable!(),
+            }
+        } else {
+            fmt_chars.next();
+            if raw_chars.next() != Some(fc) {
+                return Err(ConversionError(format!("cannot parse '{}' with format '{}'", raw, fmt)));
+            }
+        }
+    }
+
+    let (year, month, day) = match (year, month, day) {
+        (Some(y), Some(m), Some(d)) => (y, m, d),
+        _ => return Err(ConversionError(format!("format '{}' must specify year, month, and day", fmt))),
+    };
+
+    Ok(days_from_civil(year, month, day) * 86_400)
+}
+
+/// Howard Hinnant's `days_from_civil`: days since the Unix epoch
+/// (1970-01-01) for a given proleptic Gregorian calendar date.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as i64;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -3,8 +3,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
 use rayon::prelude::*;
 
+use crate::conversion::Conversion;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CutoffRecord {
     pub id: String,
@@ -23,6 +26,30 @@ pub struct CutoffRecord {
     pub stream: String,
 }
 
+/// Options controlling how `process_cutoff_data` ingests a batch. Callers
+/// declare per-field conversions (e.g. `{"opening_rank": "int", "year": "int"}`)
+/// for source files that ship ranks as `"1,234"`-style strings or dates that
+/// don't match the default ISO layout.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProcessingOptions {
+    #[serde(default)]
+    pub field_conversions: HashMap<String, String>,
+}
+
+/// One row that failed to coerce into a `CutoffRecord`, identified by its
+/// index in the submitted batch rather than aborting the whole batch.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RowError {
+    pub index: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessCutoffResult {
+    pub records: Vec<CutoffRecord>,
+    pub errors: Vec<RowError>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CutoffFilters {
     pub year: Option<u32>,
@@ -38,6 +65,37 @@ pub struct CutoffFilters {
     pub stream: Option<String>,
 }
 
+/// Controls the Graphviz header keyword and edge operator used by
+/// `DataProcessor::export_dot` — directed `digraph`/`->` for the
+/// college/course relationship, undirected `graph`/`--` if a caller ever
+/// wants a symmetric rendering.
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    Digraph,
+    Graph,
+}
+
+impl Kind {
+    fn header_keyword(self) -> &'static str {
+        match self {
+            Kind::Digraph => "digraph",
+            Kind::Graph => "graph",
+        }
+    }
+
+    fn edge_operator(self) -> &'static str {
+        match self {
+            Kind::Digraph => "->",
+            Kind::Graph => "--",
+        }
+    }
+}
+
+/// Escape a label for safe embedding in a DOT quoted string.
+fn escape_dot_label(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DataProcessorStats {
     pub total_records_processed: u64,
@@ -70,31 +128,52 @@ impl DataProcessor {
         }
     }
 
-    pub fn process_cutoff_data(&mut self, json_data: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Ingest a batch of cutoff rows. `options_json` (pass `"{}"` for
+    /// defaults) may declare per-field `Conversion`s so rows with
+    /// comma-grouped ranks, blank fields, or float-typed years coerce
+    /// instead of hard-failing; rows that still don't coerce are skipped
+    /// and reported in `errors` rather than aborting the whole batch.
+    pub fn process_cutoff_data(&mut self, json_data: &str, options_json: &str) -> Result<String, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
-        
-        // Parse JSON data
-        let records: Vec<CutoffRecord> = serde_json::from_str(json_data)?;
-        
-        // Process records in parallel
-        let processed_records: Vec<CutoffRecord> = records
-            .par_iter()
-            .map(|record| self.process_record(record))
-            .collect();
-        
+
+        let options: ProcessingOptions = if options_json.trim().is_empty() {
+            ProcessingOptions::default()
+        } else {
+            serde_json::from_str(options_json)?
+        };
+
+        let mut conversions: HashMap<String, Conversion> = HashMap::new();
+        for (field, spec) in &options.field_conversions {
+            conversions.insert(field.clone(), Conversion::from_str(spec)?);
+        }
+
+        // Deserialize to raw JSON values first so a field that doesn't fit
+        // the strict `CutoffRecord` shape (a rank shipped as a string, say)
+        // doesn't fail the whole batch before conversions get a chance to run.
+        let raw_rows: Vec<serde_json::Value> = serde_json::from_str(json_data)?;
+
+        let mut new_records = Vec::with_capacity(raw_rows.len());
+        let mut errors = Vec::new();
+        for (index, raw_row) in raw_rows.iter().enumerate() {
+            match build_cutoff_record(raw_row, &conversions) {
+                Ok(record) => new_records.push(self.process_record(&record)),
+                Err(message) => errors.push(RowError { index, message }),
+            }
+        }
+
         // Update internal records
-        self.records.extend(processed_records);
-        
+        self.records.extend(new_records);
+
         let processing_time = start_time.elapsed().as_secs_f64() * 1000.0; // Convert to milliseconds
-        
+
         // Update statistics
-        self.stats.total_records_processed += records.len() as u64;
+        self.stats.total_records_processed += raw_rows.len() as u64;
         self.stats.total_records_indexed = self.records.len() as u64;
         self.processing_times.push(processing_time);
         self.update_average_processing_time();
-        
-        // Return processed data
-        serde_json::to_string(&self.records)
+
+        // Return the full accumulated record set plus this batch's row errors
+        serde_json::to_string(&ProcessCutoffResult { records: self.records.clone(), errors })
             .map_err(|e| format!("Serialization error: {}", e).into())
     }
 
@@ -129,6 +208,74 @@ impl DataProcessor {
             .map_err(|e| format!("Serialization error: {}", e).into())
     }
 
+    /// Render filtered records as Graphviz DOT text: one node per
+    /// `group_by` value (college/state/category/level/stream/counselling_body,
+    /// defaulting to college) and per course, with an edge per
+    /// (group, course, year) labeled with that year's opening-closing rank
+    /// range. Lets the frontend render trend graphs without a charting
+    /// dependency in the WASM bundle.
+    pub fn export_dot(&self, filters_json: &str, group_by: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let filters: CutoffFilters = serde_json::from_str(filters_json)?;
+
+        let filtered: Vec<&CutoffRecord> =
+            self.records.iter().filter(|record| self.matches_filters(record, &filters)).collect();
+
+        // (group label, course, year) -> (min opening rank, max closing rank)
+        let mut edges: std::collections::BTreeMap<(String, String, u32), (u32, u32)> =
+            std::collections::BTreeMap::new();
+
+        for record in &filtered {
+            let group_label = self.group_field(record, group_by);
+            let key = (group_label.to_string(), record.course_name.clone(), record.year);
+            edges
+                .entry(key)
+                .and_modify(|(min_open, max_close)| {
+                    *min_open = (*min_open).min(record.opening_rank);
+                    *max_close = (*max_close).max(record.closing_rank);
+                })
+                .or_insert((record.opening_rank, record.closing_rank));
+        }
+
+        let kind = Kind::Digraph;
+        let mut dot = String::new();
+        dot.push_str(&format!("{} \"cutoffs\" {{\n", kind.header_keyword()));
+
+        let mut nodes: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+        for (group_label, course, _) in edges.keys() {
+            nodes.insert(group_label.clone());
+            nodes.insert(course.clone());
+        }
+        for node in &nodes {
+            dot.push_str(&format!("  \"{}\" [label=\"{}\"];\n", escape_dot_label(node), escape_dot_label(node)));
+        }
+
+        for ((group_label, course, year), (min_open, max_close)) in &edges {
+            dot.push_str(&format!(
+                "  \"{}\" {} \"{}\" [label=\"{}: {}-{}\"];\n",
+                escape_dot_label(group_label),
+                kind.edge_operator(),
+                escape_dot_label(course),
+                year,
+                min_open,
+                max_close,
+            ));
+        }
+
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+
+    fn group_field<'a>(&self, record: &'a CutoffRecord, group_by: &str) -> &'a str {
+        match group_by {
+            "state" => &record.state,
+            "category" => &record.category,
+            "level" => &record.level,
+            "stream" => &record.stream,
+            "counselling_body" => &record.counselling_body,
+            _ => &record.college_name,
+        }
+    }
+
     fn process_record(&self, record: &CutoffRecord) -> CutoffRecord {
         // Apply any data processing logic here
         // For now, just return the record as-is
@@ -251,3 +398,63 @@ impl DataProcessor {
         self.search_times.clear();
     }
 }
+
+/// Render a JSON value as the raw text a `Conversion` expects: strings pass
+/// through unchanged, numbers/bools stringify, and null/missing becomes an
+/// empty string (which every `Conversion` treats as null).
+fn json_value_to_raw_string(value: Option<&serde_json::Value>) -> String {
+    match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Number(n)) => n.to_string(),
+        Some(serde_json::Value::Bool(b)) => b.to_string(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Assemble a `CutoffRecord` from one raw JSON row, applying the
+/// caller-declared `Conversion` for each field (falling back to `AsIs` for
+/// text fields and `Integer` for rank/year/round fields). Returns a single
+/// error message identifying the offending field rather than panicking, so
+/// the caller can skip just this row.
+fn build_cutoff_record(
+    row: &serde_json::Value,
+    conversions: &HashMap<String, Conversion>,
+) -> Result<CutoffRecord, String> {
+    let obj = row.as_object().ok_or_else(|| "row is not a JSON object".to_string())?;
+
+    let text_field = |field: &str| -> Result<String, String> {
+        let raw = json_value_to_raw_string(obj.get(field));
+        let conversion = conversions.get(field).cloned().unwrap_or(Conversion::AsIs);
+        conversion
+            .convert(&raw)
+            .map(|v| v.as_text())
+            .map_err(|e| format!("field '{}': {}", field, e))
+    };
+
+    let rank_field = |field: &str| -> Result<u32, String> {
+        let raw = json_value_to_raw_string(obj.get(field));
+        let conversion = conversions.get(field).cloned().unwrap_or(Conversion::Integer);
+        let typed = conversion.convert(&raw).map_err(|e| format!("field '{}': {}", field, e))?;
+        typed
+            .as_u32()
+            .ok_or_else(|| format!("field '{}': value '{}' is not a non-negative integer", field, raw))
+    };
+
+    Ok(CutoffRecord {
+        id: text_field("id")?,
+        college_id: text_field("college_id")?,
+        college_name: text_field("college_name")?,
+        course_id: text_field("course_id")?,
+        course_name: text_field("course_name")?,
+        year: rank_field("year")?,
+        round: rank_field("round")?,
+        opening_rank: rank_field("opening_rank")?,
+        closing_rank: rank_field("closing_rank")?,
+        category: text_field("category")?,
+        state: text_field("state")?,
+        counselling_body: text_field("counselling_body")?,
+        level: text_field("level")?,
+        stream: text_field("stream")?,
+    })
+}
@@ -0,0 +1,591 @@
+// Pure-Rust DEFLATE (RFC 1951) and zlib (RFC 1950) codec.
+// No native dependencies, so it compiles to WASM exactly like the rest of
+// this crate while still interoperating with gzip/zlib content from the
+// browser or other services.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Controls the lazy-matching effort in the LZ77 stage. `Fast` checks fewer
+/// hash-chain candidates per position; `Best` checks more and looks one byte
+/// ahead before committing to a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeflateMode {
+    Fast,
+    Best,
+}
+
+enum Token {
+    Literal(u8),
+    Match { length: usize, distance: usize },
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self { bytes: Vec::new(), bit_buf: 0, bit_count: 0 }
+    }
+
+    // DEFLATE packs most fields LSB-first.
+    fn write_bits(&mut self, value: u32, bits: u32) {
+        self.bit_buf |= value << self.bit_count;
+        self.bit_count += bits;
+        while self.bit_count >= 8 {
+            self.bytes.push((self.bit_buf & 0xFF) as u8);
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    // Huffman codes are the exception: packed starting with the
+    // most-significant bit of the code (RFC 1951 section 3.1.1).
+    fn write_huffman_bits(&mut self, code: u32, bits: u32) {
+        for i in (0..bits).rev() {
+            self.write_bits((code >> i) & 1, 1);
+        }
+    }
+
+    fn align_byte(&mut self) {
+        if self.bit_count > 0 {
+            self.bytes.push((self.bit_buf & 0xFF) as u8);
+            self.bit_buf = 0;
+            self.bit_count = 0;
+        }
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align_byte();
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    byte_pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, byte_pos: 0, bit_buf: 0, bit_count: 0 }
+    }
+
+    fn read_bits(&mut self, bits: u32) -> Result<u32, Box<dyn std::error::Error>> {
+        while self.bit_count < bits {
+            if self.byte_pos >= self.data.len() {
+                return Err("unexpected end of deflate stream".into());
+            }
+            self.bit_buf |= (self.data[self.byte_pos] as u32) << self.bit_count;
+            self.byte_pos += 1;
+            self.bit_count += 8;
+        }
+        let result = if bits == 0 { 0 } else { self.bit_buf & ((1u32 << bits) - 1) };
+        self.bit_buf >>= bits;
+        self.bit_count -= bits;
+        Ok(result)
+    }
+
+    fn align_to_byte(&mut self) {
+        let discard = self.bit_count % 8;
+        self.bit_buf >>= discard;
+        self.bit_count -= discard;
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Box<dyn std::error::Error>> {
+        if self.bit_count >= 8 {
+            let byte = (self.bit_buf & 0xFF) as u8;
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+            Ok(byte)
+        } else if self.byte_pos < self.data.len() {
+            let byte = self.data[self.byte_pos];
+            self.byte_pos += 1;
+            Ok(byte)
+        } else {
+            Err("unexpected end of deflate stream".into())
+        }
+    }
+}
+
+fn length_code(len: usize) -> (usize, u8, u16) {
+    let len = len as u16;
+    for i in (0..LENGTH_BASE.len()).rev() {
+        if len >= LENGTH_BASE[i] {
+            return (257 + i, LENGTH_EXTRA[i], len - LENGTH_BASE[i]);
+        }
+    }
+    (257, 0, 0)
+}
+
+fn distance_code(dist: usize) -> (usize, u8, u16) {
+    let dist = dist as u16;
+    for i in (0..DIST_BASE.len()).rev() {
+        if dist >= DIST_BASE[i] {
+            return (i, DIST_EXTRA[i], dist - DIST_BASE[i]);
+        }
+    }
+    (0, 0, 0)
+}
+
+fn fixed_literal_code(sym: usize) -> (u32, u32) {
+    if sym <= 143 {
+        (0x030 + sym as u32, 8)
+    } else if sym <= 255 {
+        (0x190 + (sym - 144) as u32, 9)
+    } else if sym <= 279 {
+        ((sym - 256) as u32, 7)
+    } else {
+        (0x0C0 + (sym - 280) as u32, 8)
+    }
+}
+
+fn fixed_distance_code(sym: usize) -> (u32, u32) {
+    (sym as u32, 5)
+}
+
+fn hash3(data: &[u8], i: usize) -> u32 {
+    ((data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32)
+        .wrapping_mul(2654435761)
+        >> 16
+}
+
+fn insert_hash(chains: &mut HashMap<u32, Vec<usize>>, data: &[u8], pos: usize) {
+    let h = hash3(data, pos);
+    chains.entry(h).or_insert_with(Vec::new).push(pos);
+}
+
+fn find_best_match(
+    data: &[u8],
+    pos: usize,
+    chains: &HashMap<u32, Vec<usize>>,
+    max_chain: usize,
+) -> Option<(usize, usize)> {
+    if pos + MIN_MATCH > data.len() {
+        return None;
+    }
+    let candidates = chains.get(&hash3(data, pos))?;
+    let window_start = pos.saturating_sub(WINDOW_SIZE);
+    let max_len = (data.len() - pos).min(MAX_MATCH);
+    let mut best: Option<(usize, usize)> = None;
+
+    for &cand in candidates.iter().rev().take(max_chain) {
+        if cand < window_start {
+            break;
+        }
+        // Lazy matching may already have inserted `pos` itself; a match
+        // against itself would have distance 0.
+        if cand >= pos {
+            continue;
+        }
+        let mut len = 0;
+        while len < max_len && data[cand + len] == data[pos + len] {
+            len += 1;
+        }
+        if len >= MIN_MATCH && best.map_or(true, |(best_len, _)| len > best_len) {
+            best = Some((len, pos - cand));
+            if len >= MAX_MATCH {
+                break;
+            }
+        }
+    }
+    best
+}
+
+/// LZ77-parse `data[start..]` into literal/match tokens. Bytes before
+/// `start` are fed into the hash chains so matches can reach back into them
+/// as a dictionary without being re-emitted themselves -- this is what lets
+/// `DeflateEncoder` carry a real trailing window across block boundaries.
+/// A one-shot parse of a whole buffer is just the `start == 0` case.
+fn lz77_parse_from(data: &[u8], start: usize, mode: DeflateMode) -> Vec<Token> {
+    let max_chain = match mode {
+        DeflateMode::Fast => 8,
+        DeflateMode::Best => 64,
+    };
+    let lazy = matches!(mode, DeflateMode::Best);
+
+    let mut tokens = Vec::new();
+    let mut chains: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut i = 0;
+    while i < start {
+        if i + MIN_MATCH <= data.len() {
+            insert_hash(&mut chains, data, i);
+        }
+        i += 1;
+    }
+
+    while i < data.len() {
+        let m = find_best_match(data, i, &chains, max_chain);
+        if i + MIN_MATCH <= data.len() {
+            insert_hash(&mut chains, data, i);
+        }
+
+        match m {
+            Some((len, dist)) => {
+                if lazy && i + 1 + MIN_MATCH <= data.len() {
+                    let lookahead = find_best_match(data, i + 1, &chains, max_chain);
+                    insert_hash(&mut chains, data, i + 1);
+                    if let Some((len2, _)) = lookahead {
+                        if len2 > len {
+                            tokens.push(Token::Literal(data[i]));
+                            i += 1;
+                            continue;
+                        }
+                    }
+                }
+                let end = (i + len).min(data.len());
+                for k in (i + 2)..end {
+                    if k + MIN_MATCH <= data.len() {
+                        insert_hash(&mut chains, data, k);
+                    }
+                }
+                tokens.push(Token::Match { length: len, distance: dist });
+                i += len;
+            }
+            None => {
+                tokens.push(Token::Literal(data[i]));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+fn encode_tokens_fixed(writer: &mut BitWriter, tokens: &[Token]) {
+    for token in tokens {
+        match *token {
+            Token::Literal(byte) => {
+                let (code, bits) = fixed_literal_code(byte as usize);
+                writer.write_huffman_bits(code, bits);
+            }
+            Token::Match { length, distance } => {
+                let (lcode, lextra_bits, lextra_val) = length_code(length);
+                let (code, bits) = fixed_literal_code(lcode);
+                writer.write_huffman_bits(code, bits);
+                if lextra_bits > 0 {
+                    writer.write_bits(lextra_val as u32, lextra_bits as u32);
+                }
+
+                let (dcode, dextra_bits, dextra_val) = distance_code(distance);
+                let (code, bits) = fixed_distance_code(dcode);
+                writer.write_huffman_bits(code, bits);
+                if dextra_bits > 0 {
+                    writer.write_bits(dextra_val as u32, dextra_bits as u32);
+                }
+            }
+        }
+    }
+    let (code, bits) = fixed_literal_code(256); // end-of-block marker
+    writer.write_huffman_bits(code, bits);
+}
+
+/// Canonical Huffman decoder built from a per-symbol code-length table, as
+/// used by both the fixed and dynamic DEFLATE block types.
+struct HuffmanDecoder {
+    lookup: HashMap<u32, u16>,
+    max_len: u32,
+}
+
+fn build_huffman(lengths: &[u8]) -> HuffmanDecoder {
+    let max_len = *lengths.iter().max().unwrap_or(&0) as u32;
+    let mut bl_count = vec![0u32; (max_len + 1) as usize];
+    for &len in lengths {
+        if len > 0 {
+            bl_count[len as usize] += 1;
+        }
+    }
+
+    let mut code = 0u32;
+    let mut next_code = vec![0u32; (max_len + 2) as usize];
+    for bits in 1..=max_len {
+        code = (code + bl_count[(bits - 1) as usize]) << 1;
+        next_code[bits as usize] = code;
+    }
+
+    let mut lookup = HashMap::new();
+    for (sym, &len) in lengths.iter().enumerate() {
+        if len == 0 {
+            continue;
+        }
+        let len = len as u32;
+        let assigned = next_code[len as usize];
+        next_code[len as usize] += 1;
+        lookup.insert((len << 16) | assigned, sym as u16);
+    }
+
+    HuffmanDecoder { lookup, max_len }
+}
+
+impl HuffmanDecoder {
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, Box<dyn std::error::Error>> {
+        let mut code = 0u32;
+        for len in 1..=self.max_len {
+            code = (code << 1) | reader.read_bits(1)?;
+            if let Some(&sym) = self.lookup.get(&((len << 16) | code)) {
+                return Ok(sym);
+            }
+        }
+        Err("invalid huffman code in deflate stream".into())
+    }
+}
+
+fn fixed_tables() -> &'static (HuffmanDecoder, HuffmanDecoder) {
+    static TABLES: OnceLock<(HuffmanDecoder, HuffmanDecoder)> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut lit_lengths = [0u8; 288];
+        lit_lengths[0..=143].fill(8);
+        lit_lengths[144..=255].fill(9);
+        lit_lengths[256..=279].fill(7);
+        lit_lengths[280..=287].fill(8);
+        let dist_lengths = [5u8; 30];
+        (build_huffman(&lit_lengths), build_huffman(&dist_lengths))
+    })
+}
+
+fn read_dynamic_tables(
+    reader: &mut BitReader,
+) -> Result<(HuffmanDecoder, HuffmanDecoder), Box<dyn std::error::Error>> {
+    let hlit = reader.read_bits(5)? as usize + 257;
+    let hdist = reader.read_bits(5)? as usize + 1;
+    let hclen = reader.read_bits(4)? as usize + 4;
+
+    let mut cl_lengths = [0u8; 19];
+    for i in 0..hclen {
+        cl_lengths[CODE_LENGTH_ORDER[i]] = reader.read_bits(3)? as u8;
+    }
+    let cl_decoder = build_huffman(&cl_lengths);
+
+    let mut lengths = Vec::with_capacity(hlit + hdist);
+    while lengths.len() < hlit + hdist {
+        match cl_decoder.decode(reader)? {
+            sym @ 0..=15 => lengths.push(sym as u8),
+            16 => {
+                let repeat = reader.read_bits(2)? + 3;
+                let prev = *lengths.last().ok_or("repeat code with no previous length")?;
+                for _ in 0..repeat {
+                    lengths.push(prev);
+                }
+            }
+            17 => {
+                let repeat = reader.read_bits(3)? + 3;
+                for _ in 0..repeat {
+                    lengths.push(0);
+                }
+            }
+            18 => {
+                let repeat = reader.read_bits(7)? + 11;
+                for _ in 0..repeat {
+                    lengths.push(0);
+                }
+            }
+            _ => return Err("invalid code length symbol".into()),
+        }
+    }
+
+    Ok((build_huffman(&lengths[..hlit]), build_huffman(&lengths[hlit..hlit + hdist])))
+}
+
+fn decode_huffman_block(
+    reader: &mut BitReader,
+    lit: &HuffmanDecoder,
+    dist: &HuffmanDecoder,
+    output: &mut Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let sym = lit.decode(reader)?;
+        if sym < 256 {
+            output.push(sym as u8);
+        } else if sym == 256 {
+            break;
+        } else {
+            let idx = (sym - 257) as usize;
+            let extra = reader.read_bits(*LENGTH_EXTRA.get(idx).ok_or("invalid length code")? as u32)?;
+            let length = LENGTH_BASE[idx] as usize + extra as usize;
+
+            let dsym = dist.decode(reader)? as usize;
+            let dextra_bits = *DIST_EXTRA.get(dsym).ok_or("invalid distance code")?;
+            let dextra = reader.read_bits(dextra_bits as u32)?;
+            let distance = DIST_BASE[dsym] as usize + dextra as usize;
+
+            if distance == 0 || distance > output.len() {
+                return Err("invalid back-reference distance".into());
+            }
+            let start = output.len() - distance;
+            for i in 0..length {
+                let byte = output[start + i];
+                output.push(byte);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A streaming DEFLATE encoder. `compress` can be called repeatedly with
+/// successive chunks of a large blob; each call flushes any block that has
+/// enough buffered data to emit while keeping a trailing window of the last
+/// `WINDOW_SIZE` bytes so matches in the next block can still reach across
+/// the boundary. `compress_end` flushes the final block and returns the
+/// complete stream. The compressed output accumulates inside the encoder
+/// until `compress_end` returns it; it is not handed out incrementally.
+pub struct DeflateEncoder {
+    mode: DeflateMode,
+    pending: Vec<u8>,
+    window: Vec<u8>,
+    writer: BitWriter,
+}
+
+impl DeflateEncoder {
+    pub fn new(mode: DeflateMode) -> Self {
+        Self { mode, pending: Vec::new(), window: Vec::new(), writer: BitWriter::new() }
+    }
+
+    pub fn compress(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        while self.pending.len() > WINDOW_SIZE * 2 {
+            let take = self.pending.len() - WINDOW_SIZE;
+            let block: Vec<u8> = self.pending.drain(..take).collect();
+            self.encode_block(&block, false);
+        }
+    }
+
+    pub fn compress_end(mut self) -> Vec<u8> {
+        let remaining = std::mem::take(&mut self.pending);
+        self.encode_block(&remaining, true);
+        self.writer.into_bytes()
+    }
+
+    fn encode_block(&mut self, data: &[u8], is_final: bool) {
+        self.writer.write_bits(if is_final { 1 } else { 0 }, 1);
+        self.writer.write_bits(1, 2); // BTYPE = 01 (fixed Huffman)
+
+        let mut combined = std::mem::take(&mut self.window);
+        let start = combined.len();
+        combined.extend_from_slice(data);
+        let tokens = lz77_parse_from(&combined, start, self.mode);
+        encode_tokens_fixed(&mut self.writer, &tokens);
+
+        let keep = combined.len().saturating_sub(WINDOW_SIZE);
+        self.window = combined[keep..].to_vec();
+    }
+}
+
+/// Compress a single buffer in one shot.
+pub fn compress(data: &[u8], mode: DeflateMode) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(mode);
+    encoder.compress(data);
+    encoder.compress_end()
+}
+
+/// Decompress a raw DEFLATE stream (stored, fixed, or dynamic Huffman
+/// blocks).
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut reader = BitReader::new(data);
+    let mut output = Vec::new();
+
+    loop {
+        let is_final = reader.read_bits(1)? == 1;
+        match reader.read_bits(2)? {
+            0 => {
+                reader.align_to_byte();
+                let len = reader.read_byte()? as u16 | ((reader.read_byte()? as u16) << 8);
+                let _nlen = reader.read_byte()? as u16 | ((reader.read_byte()? as u16) << 8);
+                for _ in 0..len {
+                    output.push(reader.read_byte()?);
+                }
+            }
+            1 => {
+                let (lit, dist) = fixed_tables();
+                decode_huffman_block(&mut reader, lit, dist, &mut output)?;
+            }
+            2 => {
+                let (lit, dist) = read_dynamic_tables(&mut reader)?;
+                decode_huffman_block(&mut reader, &lit, &dist, &mut output)?;
+            }
+            _ => return Err("reserved deflate block type".into()),
+        }
+        if is_final {
+            break;
+        }
+    }
+
+    Ok(output)
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+    for &byte in data {
+        a = (a + byte as u32) % MOD_ADLER;
+        b = (b + a) % MOD_ADLER;
+    }
+    (b << 16) | a
+}
+
+/// Wrap a DEFLATE stream in a zlib (RFC 1950) header and Adler-32 trailer so
+/// it's directly interoperable with `pako`/`zlib.js` on the browser side.
+pub fn compress_zlib(data: &[u8], mode: DeflateMode) -> Vec<u8> {
+    let cmf: u8 = 0x78; // CM = 8 (deflate), CINFO = 7 (32K window)
+    let flevel: u8 = match mode {
+        DeflateMode::Fast => 0,
+        DeflateMode::Best => 3,
+    };
+    let mut flg = flevel << 6;
+    let remainder = ((cmf as u16) * 256 + flg as u16) % 31;
+    if remainder != 0 {
+        flg += (31 - remainder) as u8;
+    }
+
+    let mut out = vec![cmf, flg];
+    out.extend_from_slice(&compress(data, mode));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+pub fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if data.len() < 6 {
+        return Err("zlib stream too short".into());
+    }
+    if data[0] & 0x0F != 8 {
+        return Err("unsupported zlib compression method".into());
+    }
+
+    let payload = &data[2..data.len() - 4];
+    let decompressed = decompress(payload)?;
+
+    let expected_adler = u32::from_be_bytes(data[data.len() - 4..].try_into().unwrap());
+    if adler32(&decompressed) != expected_adler {
+        return Err("zlib Adler-32 checksum mismatch".into());
+    }
+
+    Ok(decompressed)
+}
@@ -0,0 +1,168 @@
+// Graph module for WebAssembly
+// College/course connectivity graph built from cutoff records, in the style
+// of classic Prolog ugraphs: a sorted, deduplicated adjacency map over
+// which reachability and transitive-closure queries can be run.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+pub type NodeId = String;
+
+/// A directed graph over `NodeId`s, stored as a `BTreeMap` adjacency list so
+/// vertex order and neighbor order are always sorted and deduplicated —
+/// the invariant that makes `transitive_closure` terminate deterministically.
+/// Edge weights (the closing rank that "earned" an edge) are tracked
+/// separately since not every graph operation needs them.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    adjacency: BTreeMap<NodeId, BTreeSet<NodeId>>,
+    edge_weights: BTreeMap<(NodeId, NodeId), u32>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self { adjacency: BTreeMap::new(), edge_weights: BTreeMap::new() }
+    }
+
+    /// Ensure `v` appears in the vertex set, even if it has no edges yet.
+    pub fn add_vertex(&mut self, v: NodeId) {
+        self.adjacency.entry(v).or_default();
+    }
+
+    /// Add a directed edge `from -> to`, optionally weighted. Adding an edge
+    /// implicitly adds both endpoints as vertices. If the edge already
+    /// exists with a weight, `weight` replaces it only when it's smaller
+    /// (a node can be reached by many records; the smallest closing rank is
+    /// the one that actually bounds reachability for a student's rank).
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: Option<u32>) {
+        self.adjacency.entry(from.clone()).or_default().insert(to.clone());
+        self.adjacency.entry(to.clone()).or_default();
+
+        if let Some(w) = weight {
+            self.edge_weights
+                .entry((from, to))
+                .and_modify(|existing| *existing = (*existing).min(w))
+                .or_insert(w);
+        }
+    }
+
+    pub fn add_edges(&mut self, edges: impl IntoIterator<Item = (NodeId, NodeId, Option<u32>)>) {
+        for (from, to, weight) in edges {
+            self.add_edge(from, to, weight);
+        }
+    }
+
+    /// Remove directed edges `(from, to)`. Vertices are left in place even
+    /// if this empties their neighbor set.
+    pub fn del_edges(&mut self, edges: &[(NodeId, NodeId)]) {
+        for (from, to) in edges {
+            if let Some(neighbors) = self.adjacency.get_mut(from) {
+                neighbors.remove(to);
+            }
+            self.edge_weights.remove(&(from.clone(), to.clone()));
+        }
+    }
+
+    /// All vertices, in sorted order.
+    pub fn vertices(&self) -> Vec<NodeId> {
+        self.adjacency.keys().cloned().collect()
+    }
+
+    /// All directed edges `(from, to)`, in sorted order.
+    pub fn edges(&self) -> Vec<(NodeId, NodeId)> {
+        self.adjacency
+            .iter()
+            .flat_map(|(from, tos)| tos.iter().map(move |to| (from.clone(), to.clone())))
+            .collect()
+    }
+
+    /// Weight (if any) previously recorded for the edge `from -> to`.
+    pub fn edge_weight(&self, from: &str, to: &str) -> Option<u32> {
+        self.edge_weights.get(&(from.to_string(), to.to_string())).copied()
+    }
+
+    pub fn neighbors(&self, v: &str) -> Vec<NodeId> {
+        self.adjacency.get(v).map(|set| set.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Reverse every edge, producing a new graph.
+    pub fn transpose(&self) -> Graph {
+        let mut reversed = Graph::new();
+        for v in self.adjacency.keys() {
+            reversed.add_vertex(v.clone());
+        }
+        for (from, to) in self.edges() {
+            let weight = self.edge_weight(&from, &to);
+            reversed.add_edge(to, from, weight);
+        }
+        reversed
+    }
+
+    /// Every node reachable from `start` via a BFS over all edges
+    /// (ignoring weight), not including `start` itself.
+    pub fn reachable(&self, start: &str) -> Vec<NodeId> {
+        let mut nodes = self.reachable_within(start, None);
+        nodes.retain(|n| n != start);
+        nodes
+    }
+
+    /// Every node reachable from `start`, pruning any edge whose weight
+    /// exceeds `max_weight` (unweighted edges are always traversable).
+    /// `max_weight = None` traverses every edge regardless of weight.
+    pub fn reachable_within(&self, start: &str, max_weight: Option<u32>) -> Vec<NodeId> {
+        let mut visited: BTreeSet<NodeId> = BTreeSet::new();
+        let mut queue: VecDeque<NodeId> = VecDeque::new();
+        queue.push_back(start.to_string());
+
+        while let Some(current) = queue.pop_front() {
+            for neighbor in self.neighbors(&current) {
+                if let Some(max) = max_weight {
+                    if let Some(weight) = self.edge_weight(&current, &neighbor) {
+                        if weight > max {
+                            continue;
+                        }
+                    }
+                }
+                if visited.insert(neighbor.clone()) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        visited.into_iter().collect()
+    }
+
+    /// Repeatedly compose adjacency with itself until no vertex gains a new
+    /// reachable neighbor, producing the transitive closure graph.
+    pub fn transitive_closure(&self) -> Graph {
+        let mut closure = self.clone();
+
+        loop {
+            let mut changed = false;
+            let snapshot = closure.adjacency.clone();
+
+            for (v, neighbors) in &snapshot {
+                let mut additions = Vec::new();
+                for n in neighbors {
+                    if let Some(next_hop) = snapshot.get(n) {
+                        for target in next_hop {
+                            if !neighbors.contains(target) && target != v {
+                                additions.push(target.clone());
+                            }
+                        }
+                    }
+                }
+                for target in additions {
+                    if closure.adjacency.get_mut(v).unwrap().insert(target) {
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        closure
+    }
+}
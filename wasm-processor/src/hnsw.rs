@@ -0,0 +1,277 @@
+// HNSW (Hierarchical Navigable Small World) approximate-nearest-neighbor
+// index, used by VectorSearchProcessor once the vector set is too large for
+// an exhaustive O(N) cosine scan to stay fast.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// A candidate node with its distance to the query, ordered so a
+/// `BinaryHeap<Candidate>` is a max-heap on distance (farthest first) and
+/// `BinaryHeap<Reverse<Candidate>>` is a min-heap (nearest first).
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    node: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.partial_cmp(&other.distance).unwrap_or(Ordering::Equal)
+    }
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 1.0;
+    }
+
+    let mut dot = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
+    for i in 0..a.len() {
+        dot += a[i] * b[i];
+        norm_a += a[i] * a[i];
+        norm_b += b[i] * b[i];
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 1.0;
+    }
+
+    1.0 - dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Approximate nearest-neighbor index over cosine distance. `M` bounds the
+/// number of neighbors kept per node per layer (doubled at layer 0), and
+/// `ef_construction`/`ef_search` bound how many candidates are kept while
+/// building/querying — higher values trade speed for recall.
+pub struct HnswIndex {
+    m: usize,
+    ef_construction: usize,
+    ml: f64,
+    vectors: Vec<Vec<f32>>,
+    ids: Vec<String>,
+    id_to_node: HashMap<String, usize>,
+    // layers[layer][node] = sorted, deduplicated neighbor list
+    layers: Vec<HashMap<usize, Vec<usize>>>,
+    node_top_layer: Vec<usize>,
+    entry_point: Option<usize>,
+    rng_state: u64,
+}
+
+impl HnswIndex {
+    pub fn new(m: usize, ef_construction: usize) -> Self {
+        Self {
+            m,
+            ef_construction,
+            ml: 1.0 / (m as f64).ln(),
+            vectors: Vec::new(),
+            ids: Vec::new(),
+            id_to_node: HashMap::new(),
+            layers: vec![HashMap::new()],
+            node_top_layer: Vec::new(),
+            entry_point: None,
+            rng_state: 0x5DEECE66D,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn next_uniform(&mut self) -> f64 {
+        self.rng_state = splitmix64(self.rng_state);
+        // Keep 53 bits of entropy, matching an f64 mantissa, scaled to (0, 1).
+        ((self.rng_state >> 11) as f64 + 1.0) / ((1u64 << 53) as f64 + 1.0)
+    }
+
+    fn random_level(&mut self) -> usize {
+        let u = self.next_uniform();
+        (-u.ln() * self.ml).floor() as usize
+    }
+
+    fn max_degree(&self, layer: usize) -> usize {
+        if layer == 0 { self.m * 2 } else { self.m }
+    }
+
+    fn distance_to(&self, node: usize, query: &[f32]) -> f32 {
+        cosine_distance(&self.vectors[node], query)
+    }
+
+    /// Greedy-search one layer from `entry_points`, keeping a dynamic
+    /// candidate set of size `ef`. Returns the `ef` closest nodes found,
+    /// sorted nearest-first.
+    fn search_layer(&self, query: &[f32], entry_points: &[usize], ef: usize, layer: usize) -> Vec<Candidate> {
+        let mut visited: std::collections::HashSet<usize> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<std::cmp::Reverse<Candidate>> = BinaryHeap::new();
+        let mut results: BinaryHeap<Candidate> = BinaryHeap::new();
+
+        for &ep in entry_points {
+            let c = Candidate { distance: self.distance_to(ep, query), node: ep };
+            candidates.push(std::cmp::Reverse(c));
+            results.push(c);
+        }
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            if let Some(farthest) = results.peek() {
+                if current.distance > farthest.distance && results.len() >= ef {
+                    break;
+                }
+            }
+
+            if let Some(neighbors) = self.layers[layer].get(&current.node) {
+                for &neighbor in neighbors {
+                    if visited.insert(neighbor) {
+                        let dist = self.distance_to(neighbor, query);
+                        let worst = results.peek().map(|c| c.distance);
+                        if results.len() < ef || worst.map_or(true, |w| dist < w) {
+                            candidates.push(std::cmp::Reverse(Candidate { distance: dist, node: neighbor }));
+                            results.push(Candidate { distance: dist, node: neighbor });
+                            if results.len() > ef {
+                                results.pop();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<Candidate> = results.into_vec();
+        out.sort();
+        out
+    }
+
+    fn connect(&mut self, layer: usize, a: usize, b: usize) {
+        let max_degree = self.max_degree(layer);
+
+        let neighbors = self.layers[layer].entry(a).or_insert_with(Vec::new);
+        if !neighbors.contains(&b) {
+            neighbors.push(b);
+        }
+        if neighbors.len() > max_degree {
+            let vector_a = self.vectors[a].clone();
+            neighbors.sort_by(|&x, &y| {
+                cosine_distance(&vector_a, &self.vectors[x])
+                    .partial_cmp(&cosine_distance(&vector_a, &self.vectors[y]))
+                    .unwrap_or(Ordering::Equal)
+            });
+            neighbors.truncate(max_degree);
+        }
+    }
+
+    /// Replace the vector stored for an already-indexed `id` in place,
+    /// without touching graph structure. Returns `false` (doing nothing) if
+    /// `id` isn't indexed yet, so the caller can fall back to `insert`.
+    /// Re-indexing this way leaves the node's neighbor links as they were
+    /// computed against the old vector; callers that update vectors often
+    /// should expect search quality to drift slowly until affected nodes are
+    /// reconnected, which this index doesn't currently do.
+    pub fn replace_vector(&mut self, id: &str, vector: Vec<f32>) -> bool {
+        match self.id_to_node.get(id) {
+            Some(&node) => {
+                self.vectors[node] = vector;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Insert a new vector, assigning it a random top layer and connecting
+    /// it to its `M` closest neighbors at every layer it participates in.
+    pub fn insert(&mut self, id: String, vector: Vec<f32>) {
+        let node = self.vectors.len();
+        self.vectors.push(vector);
+        self.ids.push(id.clone());
+        let level = self.random_level();
+        self.node_top_layer.push(level);
+        self.id_to_node.insert(id, node);
+
+        while self.layers.len() <= level {
+            self.layers.push(HashMap::new());
+        }
+
+        let entry = match self.entry_point {
+            None => {
+                self.entry_point = Some(node);
+                return;
+            }
+            Some(ep) => ep,
+        };
+
+        let query = self.vectors[node].clone();
+        let top_layer = self.node_top_layer[entry];
+
+        let mut current_nearest = entry;
+        for layer in (level + 1..=top_layer).rev() {
+            let found = self.search_layer(&query, &[current_nearest], 1, layer.min(self.layers.len() - 1));
+            if let Some(best) = found.first() {
+                current_nearest = best.node;
+            }
+        }
+
+        let mut entry_points = vec![current_nearest];
+        for layer in (0..=level.min(top_layer)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, self.ef_construction, layer);
+            let m = self.max_degree(layer).min(self.m);
+            for candidate in candidates.iter().take(m) {
+                self.connect(layer, node, candidate.node);
+                self.connect(layer, candidate.node, node);
+            }
+            entry_points = candidates.into_iter().map(|c| c.node).collect();
+            if entry_points.is_empty() {
+                entry_points = vec![current_nearest];
+            }
+        }
+
+        if level > top_layer {
+            self.entry_point = Some(node);
+        }
+    }
+
+    /// Query for the `limit` nearest neighbors by cosine similarity, using
+    /// a dynamic candidate list of size `ef` at layer 0.
+    pub fn search(&self, query: &[f32], ef: usize, limit: usize) -> Vec<(String, f32)> {
+        let entry = match self.entry_point {
+            Some(ep) => ep,
+            None => return Vec::new(),
+        };
+
+        let top_layer = self.node_top_layer[entry];
+        let mut current_nearest = entry;
+        for layer in (1..=top_layer).rev() {
+            let found = self.search_layer(query, &[current_nearest], 1, layer);
+            if let Some(best) = found.first() {
+                current_nearest = best.node;
+            }
+        }
+
+        let mut results = self.search_layer(query, &[current_nearest], ef.max(limit), 0);
+        results.truncate(limit);
+
+        results
+            .into_iter()
+            .map(|c| (self.ids[c.node].clone(), 1.0 - c.distance))
+            .collect()
+    }
+}
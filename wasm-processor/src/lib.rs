@@ -9,12 +9,22 @@ use rayon::prelude::*;
 
 // Re-export modules
 mod compression;
+mod conversion;
+mod deflate;
+mod graph;
+mod hnsw;
+mod rules;
+mod stats;
 mod vector_search;
 mod data_processor;
 mod analytics;
 mod memory_manager;
 
 use compression::CompressionProcessor;
+use deflate::DeflateMode;
+use data_processor::CutoffRecord;
+use graph::Graph;
+use rules::ValidationConfig;
 use vector_search::VectorSearchProcessor;
 use data_processor::DataProcessor;
 use analytics::AnalyticsProcessor;
@@ -26,6 +36,7 @@ static VECTOR_SEARCH_PROCESSOR: Mutex<Option<VectorSearchProcessor>> = Mutex::ne
 static DATA_PROCESSOR: Mutex<Option<DataProcessor>> = Mutex::new(None);
 static ANALYTICS_PROCESSOR: Mutex<Option<AnalyticsProcessor>> = Mutex::new(None);
 static MEMORY_MANAGER: Mutex<Option<MemoryManager>> = Mutex::new(None);
+static CUTOFF_GRAPH: Mutex<Option<Graph>> = Mutex::new(None);
 
 /// Initialize the WebAssembly processor
 #[wasm_bindgen]
@@ -81,13 +92,134 @@ pub fn decompress_lz4(compressed_data: &[u8]) -> Result<Vec<u8>, JsValue> {
 //     // Implementation would go here
 // }
 
-/// Process cutoff data with high performance
+/// Compress data with the pure-Rust DEFLATE/zlib backend. `mode` is
+/// `"fast"` or `"best"`, controlling the lazy-matching effort
 #[wasm_bindgen]
-pub fn process_cutoff_data(json_data: &str) -> Result<String, JsValue> {
+pub fn compress_deflate(data: &[u8], mode: &str) -> Result<Vec<u8>, JsValue> {
+    let mode = match mode {
+        "best" => DeflateMode::Best,
+        _ => DeflateMode::Fast,
+    };
+
+    let mut processor = COMPRESSION_PROCESSOR.lock().unwrap();
+    let processor = processor.as_mut().ok_or("Compression processor not initialized")?;
+
+    processor.compress_deflate(data, mode)
+        .map_err(|e| JsValue::from_str(&format!("Compression error: {}", e)))
+}
+
+/// Decompress a zlib-wrapped DEFLATE stream produced by `compress_deflate`
+/// or any standard zlib/gzip-compatible encoder
+#[wasm_bindgen]
+pub fn decompress_deflate(compressed_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let mut processor = COMPRESSION_PROCESSOR.lock().unwrap();
+    let processor = processor.as_mut().ok_or("Compression processor not initialized")?;
+
+    processor.decompress_deflate(compressed_data)
+        .map_err(|e| JsValue::from_str(&format!("Decompression error: {}", e)))
+}
+
+/// Compress data with content-defined chunking and cross-dataset dedup,
+/// returning the chunk manifest as JSON
+#[wasm_bindgen]
+pub fn compress_chunked(data: &[u8]) -> Result<String, JsValue> {
+    let mut processor = COMPRESSION_PROCESSOR.lock().unwrap();
+    let processor = processor.as_mut().ok_or("Compression processor not initialized")?;
+
+    let manifest = processor.compress_chunked(data)
+        .map_err(|e| JsValue::from_str(&format!("Compression error: {}", e)))?;
+
+    serde_json::to_string(&manifest)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Reassemble data from a chunk manifest produced by `compress_chunked`
+#[wasm_bindgen]
+pub fn decompress_chunked(manifest_json: &str) -> Result<Vec<u8>, JsValue> {
+    let processor = COMPRESSION_PROCESSOR.lock().unwrap();
+    let processor = processor.as_ref().ok_or("Compression processor not initialized")?;
+
+    let manifest = serde_json::from_str(manifest_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid manifest: {}", e)))?;
+
+    processor.decompress_chunked(&manifest)
+        .map_err(|e| JsValue::from_str(&format!("Decompression error: {}", e)))
+}
+
+/// Build (or rebuild) the college/course connectivity graph from cutoff
+/// records: a directed edge college_id -> course_id for every record,
+/// weighted by closing rank. Use `reachable_nodes` afterwards to query it.
+#[wasm_bindgen]
+pub fn build_cutoff_graph(json_data: &str) -> Result<(), JsValue> {
+    let records: Vec<CutoffRecord> = serde_json::from_str(json_data)
+        .map_err(|e| JsValue::from_str(&format!("Invalid cutoff data: {}", e)))?;
+
+    let mut g = Graph::new();
+    for record in &records {
+        g.add_edge(record.college_id.clone(), record.course_id.clone(), Some(record.closing_rank));
+    }
+
+    *CUTOFF_GRAPH.lock().unwrap() = Some(g);
+    Ok(())
+}
+
+/// Every college/course reachable from `node_id` whose edge weight (the
+/// closing rank that earned it) doesn't exceed `max_rank`, as a JSON array
+/// of node ids. Call `build_cutoff_graph` first.
+#[wasm_bindgen]
+pub fn reachable_nodes(node_id: &str, max_rank: u32) -> Result<String, JsValue> {
+    let graph = CUTOFF_GRAPH.lock().unwrap();
+    let graph = graph.as_ref().ok_or("Cutoff graph not built — call build_cutoff_graph first")?;
+
+    let nodes = graph.reachable_within(node_id, Some(max_rank));
+    serde_json::to_string(&nodes)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Render filtered cutoff records as Graphviz DOT text, grouped by
+/// `group_by` (college/state/category/level/stream/counselling_body), for
+/// client-side trend visualization without a charting dependency.
+#[wasm_bindgen]
+pub fn export_cutoffs_dot(filters_json: &str, group_by: &str) -> Result<String, JsValue> {
+    let processor = DATA_PROCESSOR.lock().unwrap();
+    let processor = processor.as_ref().ok_or("Data processor not initialized")?;
+
+    processor.export_dot(filters_json, group_by)
+        .map_err(|e| JsValue::from_str(&format!("Export error: {}", e)))
+}
+
+/// Run the built-in validation rules (rank ordering, year sanity,
+/// non-empty ids, cross-record duplicate detection) over a batch of cutoff
+/// records and return the collected diagnostics as JSON. `config_json`
+/// (pass `"{}"` for defaults) may disable individual rules by name or remap
+/// their severities, e.g. `{"disabled_rules": ["year_sanity"], "severity_overrides": {"duplicate_key": "error"}}`.
+#[wasm_bindgen]
+pub fn validate_cutoffs(json_data: &str, config_json: &str) -> Result<String, JsValue> {
+    let records: Vec<CutoffRecord> = serde_json::from_str(json_data)
+        .map_err(|e| JsValue::from_str(&format!("Invalid cutoff data: {}", e)))?;
+
+    let config: ValidationConfig = if config_json.trim().is_empty() {
+        ValidationConfig::default()
+    } else {
+        serde_json::from_str(config_json).map_err(|e| JsValue::from_str(&format!("Invalid config: {}", e)))?
+    };
+
+    let diagnostics = rules::validate_records(&records, &config).map_err(|e| JsValue::from_str(&e))?;
+
+    serde_json::to_string(&diagnostics)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
+
+/// Process cutoff data with high performance. `options_json` (pass `"{}"`
+/// for defaults) may declare per-field conversions, e.g.
+/// `{"field_conversions": {"opening_rank": "int", "year": "int"}}`, so rows
+/// with comma-grouped ranks or blank fields coerce instead of hard-failing.
+#[wasm_bindgen]
+pub fn process_cutoff_data(json_data: &str, options_json: &str) -> Result<String, JsValue> {
     let mut processor = DATA_PROCESSOR.lock().unwrap();
     let processor = processor.as_mut().ok_or("Data processor not initialized")?;
-    
-    processor.process_cutoff_data(json_data)
+
+    processor.process_cutoff_data(json_data, options_json)
         .map_err(|e| JsValue::from_str(&format!("Processing error: {}", e)))
 }
 
@@ -116,11 +248,34 @@ pub fn search_by_vector(query_vector: &[f32], limit: usize) -> Result<String, Js
 pub fn generate_embedding(text: &str) -> Result<Vec<f32>, JsValue> {
     let mut processor = VECTOR_SEARCH_PROCESSOR.lock().unwrap();
     let processor = processor.as_mut().ok_or("Vector search processor not initialized")?;
-    
+
     processor.generate_embedding(text)
         .map_err(|e| JsValue::from_str(&format!("Embedding generation error: {}", e)))
 }
 
+/// Tune the HNSW approximate-nearest-neighbor index and rebuild it from the
+/// currently indexed vectors
+#[wasm_bindgen]
+pub fn configure_hnsw(ef_search: usize, m: usize, ef_construction: usize) -> Result<(), JsValue> {
+    let mut processor = VECTOR_SEARCH_PROCESSOR.lock().unwrap();
+    let processor = processor.as_mut().ok_or("Vector search processor not initialized")?;
+
+    processor.configure_hnsw(ef_search, m, ef_construction);
+    Ok(())
+}
+
+/// Hybrid lexical (BM25) + vector similarity search, fused with
+/// reciprocal rank fusion so exact keyword matches surface even when the
+/// embedding similarity is weak
+#[wasm_bindgen]
+pub fn search_hybrid(query_text: &str, query_vector: &[f32], limit: usize) -> Result<String, JsValue> {
+    let mut processor = VECTOR_SEARCH_PROCESSOR.lock().unwrap();
+    let processor = processor.as_mut().ok_or("Vector search processor not initialized")?;
+
+    processor.search_hybrid(query_text, query_vector, limit)
+        .map_err(|e| JsValue::from_str(&format!("Hybrid search error: {}", e)))
+}
+
 /// Calculate analytics
 #[wasm_bindgen]
 pub fn calculate_analytics(data_json: &str) -> Result<String, JsValue> {
@@ -158,8 +313,8 @@ pub fn clear_data() -> Result<(), JsValue> {
         }
     }
     
-    if let Ok(mut manager) = MEMORY_MANAGER.lock() {
-        if let Some(m) = manager.as_mut() {
+    if let Ok(manager) = MEMORY_MANAGER.lock() {
+        if let Some(m) = manager.as_ref() {
             m.clear_all();
         }
     }
@@ -192,7 +347,43 @@ pub fn get_performance_stats() -> Result<String, JsValue> {
             stats.insert("data_processing".to_string(), serde_json::to_value(p.get_stats()).unwrap());
         }
     }
-    
+
     serde_json::to_string(&stats)
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
 }
+
+/// Poll every processor for a periodic (default every 10s) delta snapshot —
+/// throughput, latency percentiles, and counts since the last call — for
+/// cheap dashboards that don't want to diff full running totals themselves.
+/// Processors that haven't crossed their interval yet are omitted.
+#[wasm_bindgen]
+pub fn get_interval_stats() -> Result<String, JsValue> {
+    let mut reports = serde_json::Map::new();
+
+    if let Ok(processor) = COMPRESSION_PROCESSOR.lock() {
+        if let Some(p) = processor.as_ref() {
+            if let Some(report) = p.maybe_interval_report() {
+                reports.insert("compression".to_string(), serde_json::from_str(&report).unwrap());
+            }
+        }
+    }
+
+    if let Ok(processor) = VECTOR_SEARCH_PROCESSOR.lock() {
+        if let Some(p) = processor.as_ref() {
+            if let Some(report) = p.maybe_interval_report() {
+                reports.insert("vector_search".to_string(), serde_json::from_str(&report).unwrap());
+            }
+        }
+    }
+
+    if let Ok(manager) = MEMORY_MANAGER.lock() {
+        if let Some(m) = manager.as_ref() {
+            if let Some(report) = m.maybe_interval_report() {
+                reports.insert("memory".to_string(), serde_json::from_str(&report).unwrap());
+            }
+        }
+    }
+
+    serde_json::to_string(&reports)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
@@ -3,6 +3,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::stats::{AtomicF64, IntervalCounts, IntervalReporter};
+
+const DEFAULT_REPORT_INTERVAL_MS: u64 = 10_000;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MemoryStats {
@@ -16,75 +22,129 @@ pub struct MemoryStats {
     pub deallocation_count: u64,
 }
 
-pub struct MemoryManager {
-    stats: MemoryStats,
-    allocations: HashMap<String, usize>,
+/// Atomic-counter-backed stats storage, mirroring `CompressionProcessor`'s
+/// `AtomicCompressionStats`. `available_memory`/`memory_pressure` are
+/// derived from `used_memory`/`total_memory` rather than stored separately,
+/// so there's nothing to keep in sync between them. `MemoryStats` is just a
+/// point-in-time snapshot of this built by `get_usage_stats`.
+struct AtomicMemoryStats {
+    used_memory: AtomicU64,
+    total_memory: AtomicU64,
+    gc_count: AtomicU64,
+    last_gc_time: AtomicF64,
+    allocation_count: AtomicU64,
+    deallocation_count: AtomicU64,
 }
 
-impl MemoryManager {
-    pub fn new() -> Self {
+impl AtomicMemoryStats {
+    fn new() -> Self {
         Self {
-            stats: MemoryStats {
-                used_memory: 0,
-                total_memory: 0,
-                available_memory: 0,
-                memory_pressure: 0.0,
-                gc_count: 0,
-                last_gc_time: 0.0,
-                allocation_count: 0,
-                deallocation_count: 0,
-            },
-            allocations: HashMap::new(),
+            used_memory: AtomicU64::new(0),
+            total_memory: AtomicU64::new(0),
+            gc_count: AtomicU64::new(0),
+            last_gc_time: AtomicF64::new(0.0),
+            allocation_count: AtomicU64::new(0),
+            deallocation_count: AtomicU64::new(0),
         }
     }
 
-    pub fn get_usage_stats(&self) -> &MemoryStats {
-        &self.stats
+    fn snapshot(&self) -> MemoryStats {
+        let used_memory = self.used_memory.load(Ordering::Relaxed);
+        let total_memory = self.total_memory.load(Ordering::Relaxed);
+        let memory_pressure = if total_memory > 0 { used_memory as f64 / total_memory as f64 } else { 0.0 };
+
+        MemoryStats {
+            used_memory,
+            total_memory,
+            available_memory: total_memory.saturating_sub(used_memory),
+            memory_pressure,
+            gc_count: self.gc_count.load(Ordering::Relaxed),
+            last_gc_time: self.last_gc_time.load(),
+            allocation_count: self.allocation_count.load(Ordering::Relaxed),
+            deallocation_count: self.deallocation_count.load(Ordering::Relaxed),
+        }
     }
 
-    pub fn allocate(&mut self, identifier: String, size: usize) {
-        self.allocations.insert(identifier, size);
-        self.stats.used_memory += size as u64;
-        self.stats.allocation_count += 1;
-        self.update_memory_pressure();
+    /// Saturating `used_memory -= amount`, via a compare-and-swap loop since
+    /// there's no saturating `fetch_sub` on `AtomicU64`.
+    fn release(&self, amount: u64) {
+        let mut current = self.used_memory.load(Ordering::Relaxed);
+        loop {
+            let next = current.saturating_sub(amount);
+            match self.used_memory.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
     }
+}
+
+pub struct MemoryManager {
+    stats: AtomicMemoryStats,
+    allocations: Mutex<HashMap<String, usize>>,
+    reporter: IntervalReporter,
+}
 
-    pub fn deallocate(&mut self, identifier: &str) -> Option<usize> {
-        if let Some(size) = self.allocations.remove(identifier) {
-            self.stats.used_memory = self.stats.used_memory.saturating_sub(size as u64);
-            self.stats.deallocation_count += 1;
-            self.update_memory_pressure();
-            Some(size)
-        } else {
-            None
+impl MemoryManager {
+    pub fn new() -> Self {
+        Self {
+            stats: AtomicMemoryStats::new(),
+            allocations: Mutex::new(HashMap::new()),
+            reporter: IntervalReporter::new(DEFAULT_REPORT_INTERVAL_MS),
         }
     }
 
-    pub fn clear_all(&mut self) {
-        self.allocations.clear();
-        self.stats.used_memory = 0;
-        self.stats.gc_count += 1;
-        self.stats.last_gc_time = js_sys::Date::now();
-        self.update_memory_pressure();
+    pub fn get_usage_stats(&self) -> MemoryStats {
+        self.stats.snapshot()
     }
 
-    fn update_memory_pressure(&mut self) {
-        // Estimate memory pressure based on used memory
-        // This is a simplified calculation
-        if self.stats.total_memory > 0 {
-            self.stats.memory_pressure = self.stats.used_memory as f64 / self.stats.total_memory as f64;
-        } else {
-            self.stats.memory_pressure = 0.0;
-        }
-        
-        self.stats.available_memory = self.stats.total_memory.saturating_sub(self.stats.used_memory);
+    pub fn allocate(&self, identifier: String, size: usize) {
+        self.allocations.lock().unwrap().insert(identifier, size);
+        self.stats.used_memory.fetch_add(size as u64, Ordering::Relaxed);
+        self.stats.allocation_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn deallocate(&self, identifier: &str) -> Option<usize> {
+        let size = self.allocations.lock().unwrap().remove(identifier)?;
+        self.stats.release(size as u64);
+        self.stats.deallocation_count.fetch_add(1, Ordering::Relaxed);
+        Some(size)
+    }
+
+    pub fn clear_all(&self) {
+        self.allocations.lock().unwrap().clear();
+        self.stats.used_memory.store(0, Ordering::Relaxed);
+        self.stats.gc_count.fetch_add(1, Ordering::Relaxed);
+        self.stats.last_gc_time.store(js_sys::Date::now());
     }
 
     pub fn get_allocation_count(&self) -> usize {
-        self.allocations.len()
+        self.allocations.lock().unwrap().len()
     }
 
     pub fn get_total_allocated_size(&self) -> usize {
-        self.allocations.values().sum()
+        self.allocations.lock().unwrap().values().sum()
+    }
+
+    /// If the reporting interval (default 10s) has elapsed, returns a JSON
+    /// snapshot of allocation/deallocation deltas and current memory
+    /// pressure since the previous report; otherwise `None`.
+    pub fn maybe_interval_report(&self) -> Option<String> {
+        let stats = self.stats.snapshot();
+        let current = IntervalCounts {
+            operations: stats.allocation_count + stats.deallocation_count,
+            bytes: stats.used_memory,
+        };
+        let (previous, elapsed_ms) = self.reporter.try_begin_emit(current)?;
+
+        let snapshot = serde_json::json!({
+            "processor": "memory",
+            "interval_ms": elapsed_ms,
+            "operations": current.operations.saturating_sub(previous.operations),
+            "used_memory": current.bytes,
+            "memory_pressure": stats.memory_pressure,
+            "gc_count": stats.gc_count,
+        });
+        Some(snapshot.to_string())
     }
 }
@@ -0,0 +1,220 @@
+// Validation rule engine run over ingested cutoff records, producing
+// severity-ranked diagnostics instead of silently accepting bad data.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::data_processor::CutoffRecord;
+
+const MIN_SANE_YEAR: u32 = 1990;
+const MAX_SANE_YEAR: u32 = 2100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Severity::Error),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "info" => Ok(Severity::Info),
+            other => Err(format!("unknown severity '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub record_id: String,
+}
+
+/// Precomputed, read-only context available to every rule check — anything
+/// that needs to look across records (like duplicate detection) is computed
+/// once up front here, since individual `check` calls run per-record over
+/// `par_iter` and can't see their siblings.
+pub struct RuleContext {
+    duplicate_counts: HashMap<(String, String, u32, u32, String), u32>,
+}
+
+impl RuleContext {
+    pub fn build(records: &[CutoffRecord]) -> Self {
+        let mut duplicate_counts = HashMap::new();
+        for record in records {
+            *duplicate_counts.entry(duplicate_key(record)).or_insert(0) += 1;
+        }
+        Self { duplicate_counts }
+    }
+
+    fn duplicate_count(&self, record: &CutoffRecord) -> u32 {
+        *self.duplicate_counts.get(&duplicate_key(record)).unwrap_or(&0)
+    }
+}
+
+fn duplicate_key(record: &CutoffRecord) -> (String, String, u32, u32, String) {
+    (record.college_id.clone(), record.course_id.clone(), record.year, record.round, record.category.clone())
+}
+
+/// A single validation check. Implementations must be stateless per record
+/// (any cross-record aggregation belongs in `RuleContext`) since records are
+/// checked concurrently via `par_iter`.
+pub trait Rule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn check(&self, record: &CutoffRecord, ctx: &RuleContext) -> Vec<Diagnostic>;
+}
+
+struct RankOrderRule;
+impl Rule for RankOrderRule {
+    fn name(&self) -> &'static str {
+        "rank_order"
+    }
+
+    fn check(&self, record: &CutoffRecord, _ctx: &RuleContext) -> Vec<Diagnostic> {
+        if record.opening_rank > record.closing_rank {
+            vec![Diagnostic {
+                rule: self.name().to_string(),
+                severity: Severity::Error,
+                message: format!(
+                    "opening_rank ({}) is greater than closing_rank ({})",
+                    record.opening_rank, record.closing_rank
+                ),
+                record_id: record.id.clone(),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+struct YearSanityRule;
+impl Rule for YearSanityRule {
+    fn name(&self) -> &'static str {
+        "year_sanity"
+    }
+
+    fn check(&self, record: &CutoffRecord, _ctx: &RuleContext) -> Vec<Diagnostic> {
+        if record.year < MIN_SANE_YEAR || record.year > MAX_SANE_YEAR {
+            vec![Diagnostic {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "year {} is outside the sane range {}-{}",
+                    record.year, MIN_SANE_YEAR, MAX_SANE_YEAR
+                ),
+                record_id: record.id.clone(),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+struct RequiredIdsRule;
+impl Rule for RequiredIdsRule {
+    fn name(&self) -> &'static str {
+        "required_ids"
+    }
+
+    fn check(&self, record: &CutoffRecord, _ctx: &RuleContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if record.college_id.trim().is_empty() {
+            diagnostics.push(Diagnostic {
+                rule: self.name().to_string(),
+                severity: Severity::Error,
+                message: "college_id is empty".to_string(),
+                record_id: record.id.clone(),
+            });
+        }
+        if record.course_id.trim().is_empty() {
+            diagnostics.push(Diagnostic {
+                rule: self.name().to_string(),
+                severity: Severity::Error,
+                message: "course_id is empty".to_string(),
+                record_id: record.id.clone(),
+            });
+        }
+        diagnostics
+    }
+}
+
+struct DuplicateKeyRule;
+impl Rule for DuplicateKeyRule {
+    fn name(&self) -> &'static str {
+        "duplicate_key"
+    }
+
+    fn check(&self, record: &CutoffRecord, ctx: &RuleContext) -> Vec<Diagnostic> {
+        if ctx.duplicate_count(record) > 1 {
+            vec![Diagnostic {
+                rule: self.name().to_string(),
+                severity: Severity::Warning,
+                message: format!(
+                    "duplicate (college_id, course_id, year, round, category) key shared by {} records",
+                    ctx.duplicate_count(record)
+                ),
+                record_id: record.id.clone(),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn default_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(RankOrderRule),
+        Box::new(YearSanityRule),
+        Box::new(RequiredIdsRule),
+        Box::new(DuplicateKeyRule),
+    ]
+}
+
+/// Caller-declared rule configuration: which built-in rules to skip, and
+/// severity remaps (e.g. treat `duplicate_key` as `Error` instead of the
+/// built-in `Warning`) so a frontend can tune strictness without forking
+/// the rule implementations.
+#[derive(Debug, Default, Deserialize)]
+pub struct ValidationConfig {
+    #[serde(default)]
+    pub disabled_rules: HashSet<String>,
+    #[serde(default)]
+    pub severity_overrides: HashMap<String, String>,
+}
+
+/// Run every enabled rule against every record, returning all diagnostics.
+/// Cross-record context (duplicate detection) is computed once up front;
+/// the per-record checks themselves run in parallel.
+pub fn validate_records(records: &[CutoffRecord], config: &ValidationConfig) -> Result<Vec<Diagnostic>, String> {
+    let ctx = RuleContext::build(records);
+    let rules: Vec<Box<dyn Rule>> =
+        default_rules().into_iter().filter(|rule| !config.disabled_rules.contains(rule.name())).collect();
+
+    let mut severity_overrides: HashMap<String, Severity> = HashMap::new();
+    for (rule_name, severity_name) in &config.severity_overrides {
+        severity_overrides.insert(rule_name.clone(), Severity::from_str(severity_name)?);
+    }
+
+    let mut diagnostics: Vec<Diagnostic> = records
+        .par_iter()
+        .flat_map(|record| rules.iter().flat_map(|rule| rule.check(record, &ctx)).collect::<Vec<_>>())
+        .collect();
+
+    for diagnostic in &mut diagnostics {
+        if let Some(&severity) = severity_overrides.get(&diagnostic.rule) {
+            diagnostic.severity = severity;
+        }
+    }
+
+    Ok(diagnostics)
+}
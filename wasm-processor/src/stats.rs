@@ -0,0 +1,168 @@
+// Shared lock-free(ish) statistics primitives used by CompressionProcessor,
+// VectorSearchProcessor, and MemoryManager. Counters and running averages
+// use atomics so they can be updated from `&self`, and latency is kept in a
+// small ring buffer instead of an ever-growing `Vec` so memory stays
+// constant under sustained load.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// An `f64` that can be read and updated through a shared reference via a
+/// compare-and-swap loop over its bit pattern. There's no `AtomicF64` in
+/// std, so this is the usual workaround.
+pub struct AtomicF64(AtomicU64);
+
+impl AtomicF64 {
+    pub fn new(value: f64) -> Self {
+        Self(AtomicU64::new(value.to_bits()))
+    }
+
+    pub fn load(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn store(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn fetch_update<F: Fn(f64) -> f64>(&self, f: F) {
+        let mut current = self.0.load(Ordering::Relaxed);
+        loop {
+            let next = f(f64::from_bits(current)).to_bits();
+            match self.0.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+/// A running mean kept as a fixed-point (sum, count) pair instead of a list
+/// of samples, so computing it is O(1) memory regardless of how many
+/// values have been recorded. The sum is kept in millionths of a unit.
+pub struct RunningAverage {
+    sum: AtomicI64,
+    count: AtomicU64,
+}
+
+const FIXED_POINT_SCALE: f64 = 1_000_000.0;
+
+impl RunningAverage {
+    pub fn new() -> Self {
+        Self { sum: AtomicI64::new(0), count: AtomicU64::new(0) }
+    }
+
+    pub fn record(&self, value: f64) {
+        self.sum.fetch_add((value * FIXED_POINT_SCALE).round() as i64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn average(&self) -> f64 {
+        let count = self.count.load(Ordering::Relaxed);
+        if count == 0 {
+            0.0
+        } else {
+            self.sum.load(Ordering::Relaxed) as f64 / FIXED_POINT_SCALE / count as f64
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.sum.store(0, Ordering::Relaxed);
+        self.count.store(0, Ordering::Relaxed);
+    }
+}
+
+/// A fixed-capacity latency buffer: the oldest sample is evicted once the
+/// ring is full, so memory is bounded no matter how long the process runs.
+/// Used for percentile estimates, which a running sum/count can't give.
+pub struct LatencyRing {
+    samples: Mutex<std::collections::VecDeque<f64>>,
+    capacity: usize,
+}
+
+impl LatencyRing {
+    pub fn new(capacity: usize) -> Self {
+        Self { samples: Mutex::new(std::collections::VecDeque::with_capacity(capacity)), capacity }
+    }
+
+    pub fn record(&self, value: f64) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(value);
+    }
+
+    /// `p` is in `[0.0, 1.0]` (e.g. 0.5 for p50, 0.99 for p99).
+    pub fn percentile(&self, p: f64) -> f64 {
+        let samples = self.samples.lock().unwrap();
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<f64> = samples.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let idx = (((sorted.len() - 1) as f64) * p).round() as usize;
+        sorted[idx]
+    }
+
+    pub fn clear(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+}
+
+/// Snapshot of the counters an interval report computes a delta against.
+#[derive(Clone, Copy, Default)]
+pub struct IntervalCounts {
+    pub operations: u64,
+    pub bytes: u64,
+}
+
+/// Gates periodic reporting (default every 10s) and tracks the counter
+/// values as of the last emitted snapshot so callers can report deltas
+/// (throughput, counts) rather than the same running totals every time.
+pub struct IntervalReporter {
+    interval_ms: u64,
+    started: Instant,
+    last_emit_ms: AtomicU64,
+    last_counts: Mutex<IntervalCounts>,
+}
+
+impl IntervalReporter {
+    pub fn new(interval_ms: u64) -> Self {
+        Self {
+            interval_ms,
+            started: Instant::now(),
+            last_emit_ms: AtomicU64::new(0),
+            last_counts: Mutex::new(IntervalCounts::default()),
+        }
+    }
+
+    /// If the interval has elapsed, atomically claims the emit slot (so
+    /// concurrent callers don't double-report) and returns the previous
+    /// snapshot plus elapsed milliseconds to compute a delta against.
+    pub fn try_begin_emit(&self, current: IntervalCounts) -> Option<(IntervalCounts, f64)> {
+        let now_ms = self.started.elapsed().as_millis() as u64;
+        let last_ms = self.last_emit_ms.load(Ordering::Relaxed);
+        let elapsed = now_ms.saturating_sub(last_ms);
+        if elapsed < self.interval_ms {
+            return None;
+        }
+        if self
+            .last_emit_ms
+            .compare_exchange(last_ms, now_ms, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return None;
+        }
+
+        let mut last_counts = self.last_counts.lock().unwrap();
+        let previous = *last_counts;
+        *last_counts = current;
+        Some((previous, elapsed.max(1) as f64))
+    }
+}
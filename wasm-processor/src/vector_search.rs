@@ -4,6 +4,30 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::hnsw::HnswIndex;
+use crate::stats::{IntervalCounts, IntervalReporter, LatencyRing, RunningAverage};
+
+// BM25 defaults (Robertson/Sparck Jones)
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+// Reciprocal rank fusion constant
+const RRF_K: f32 = 60.0;
+
+// Below this many indexed vectors, the exhaustive O(N) cosine scan is fast
+// enough that the HNSW approximation isn't worth its recall loss.
+const HNSW_BRUTE_FORCE_THRESHOLD: usize = 1_000;
+const DEFAULT_HNSW_M: usize = 16;
+const DEFAULT_HNSW_EF_CONSTRUCTION: usize = 100;
+const DEFAULT_HNSW_EF_SEARCH: usize = 50;
+// Sample one in every N HNSW searches against the brute-force ground truth
+// to keep a running recall estimate without paying the O(N) cost every call.
+const RECALL_SAMPLE_INTERVAL: u64 = 10;
+
+// Bound on how many recent latency samples are kept for percentile estimates.
+const LATENCY_RING_CAPACITY: usize = 256;
+const DEFAULT_REPORT_INTERVAL_MS: u64 = 10_000;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VectorSearchStats {
@@ -12,6 +36,11 @@ pub struct VectorSearchStats {
     pub average_search_time: f64,
     pub average_embedding_time: f64,
     pub total_vectors_indexed: u64,
+    pub total_hybrid_searches: u64,
+    pub average_hybrid_search_time: f64,
+    pub total_hnsw_searches: u64,
+    pub total_brute_force_searches: u64,
+    pub average_recall_estimate: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,12 +50,75 @@ pub struct SearchResult {
     pub metadata: HashMap<String, String>,
 }
 
+/// Atomic-counter-backed stats storage, mirroring `CompressionProcessor`'s
+/// `AtomicCompressionStats`. `VectorSearchStats` is a point-in-time
+/// snapshot built by `get_stats`.
+struct AtomicVectorSearchStats {
+    total_searches: AtomicU64,
+    total_embeddings_generated: AtomicU64,
+    search_time: RunningAverage,
+    embedding_time: RunningAverage,
+    total_vectors_indexed: AtomicU64,
+    total_hybrid_searches: AtomicU64,
+    hybrid_search_time: RunningAverage,
+    total_hnsw_searches: AtomicU64,
+    total_brute_force_searches: AtomicU64,
+    recall_estimate: RunningAverage,
+}
+
+impl AtomicVectorSearchStats {
+    fn new() -> Self {
+        Self {
+            total_searches: AtomicU64::new(0),
+            total_embeddings_generated: AtomicU64::new(0),
+            search_time: RunningAverage::new(),
+            embedding_time: RunningAverage::new(),
+            total_vectors_indexed: AtomicU64::new(0),
+            total_hybrid_searches: AtomicU64::new(0),
+            hybrid_search_time: RunningAverage::new(),
+            total_hnsw_searches: AtomicU64::new(0),
+            total_brute_force_searches: AtomicU64::new(0),
+            // Empty until the first sample comes in; `snapshot` reports 1.0
+            // (perfect recall assumed) while it is empty.
+            recall_estimate: RunningAverage::new(),
+        }
+    }
+
+    fn snapshot(&self) -> VectorSearchStats {
+        VectorSearchStats {
+            total_searches: self.total_searches.load(Ordering::Relaxed),
+            total_embeddings_generated: self.total_embeddings_generated.load(Ordering::Relaxed),
+            average_search_time: self.search_time.average(),
+            average_embedding_time: self.embedding_time.average(),
+            total_vectors_indexed: self.total_vectors_indexed.load(Ordering::Relaxed),
+            total_hybrid_searches: self.total_hybrid_searches.load(Ordering::Relaxed),
+            average_hybrid_search_time: self.hybrid_search_time.average(),
+            total_hnsw_searches: self.total_hnsw_searches.load(Ordering::Relaxed),
+            total_brute_force_searches: self.total_brute_force_searches.load(Ordering::Relaxed),
+            average_recall_estimate: if self.recall_estimate.count() == 0 {
+                1.0
+            } else {
+                self.recall_estimate.average()
+            },
+        }
+    }
+}
+
 pub struct VectorSearchProcessor {
     vectors: HashMap<String, Vec<f32>>,
     metadata: HashMap<String, HashMap<String, String>>,
-    stats: VectorSearchStats,
-    search_times: Vec<f64>,
-    embedding_times: Vec<f64>,
+    stats: AtomicVectorSearchStats,
+    search_latency: LatencyRing,
+    embedding_latency: LatencyRing,
+    reporter: IntervalReporter,
+    // BM25 lexical index, keyed the same way as `vectors`/`metadata`
+    doc_term_counts: HashMap<String, HashMap<String, u32>>,
+    doc_lengths: HashMap<String, u32>,
+    document_frequency: HashMap<String, u32>,
+    total_doc_length: u64,
+    hnsw: HnswIndex,
+    hnsw_ef_search: usize,
+    recall_sample_counter: u64,
 }
 
 impl VectorSearchProcessor {
@@ -34,72 +126,251 @@ impl VectorSearchProcessor {
         Self {
             vectors: HashMap::new(),
             metadata: HashMap::new(),
-            stats: VectorSearchStats {
-                total_searches: 0,
-                total_embeddings_generated: 0,
-                average_search_time: 0.0,
-                average_embedding_time: 0.0,
-                total_vectors_indexed: 0,
-            },
-            search_times: Vec::new(),
-            embedding_times: Vec::new(),
+            stats: AtomicVectorSearchStats::new(),
+            search_latency: LatencyRing::new(LATENCY_RING_CAPACITY),
+            embedding_latency: LatencyRing::new(LATENCY_RING_CAPACITY),
+            reporter: IntervalReporter::new(DEFAULT_REPORT_INTERVAL_MS),
+            doc_term_counts: HashMap::new(),
+            doc_lengths: HashMap::new(),
+            document_frequency: HashMap::new(),
+            total_doc_length: 0,
+            hnsw: HnswIndex::new(DEFAULT_HNSW_M, DEFAULT_HNSW_EF_CONSTRUCTION),
+            hnsw_ef_search: DEFAULT_HNSW_EF_SEARCH,
+            recall_sample_counter: 0,
+        }
+    }
+
+    /// Tune the HNSW index. `m` bounds neighbors kept per node per layer;
+    /// `ef` bounds the candidate set size used at query time. The index is
+    /// rebuilt from every vector already indexed.
+    pub fn configure_hnsw(&mut self, ef_search: usize, m: usize, ef_construction: usize) {
+        self.hnsw = HnswIndex::new(m, ef_construction);
+        self.hnsw_ef_search = ef_search;
+        for (id, vector) in self.vectors.clone() {
+            self.hnsw.insert(id, vector);
         }
     }
 
     pub fn add_vector(&mut self, id: String, vector: Vec<f32>, metadata: HashMap<String, String>) {
-        self.vectors.insert(id.clone(), vector);
+        self.vectors.insert(id.clone(), vector.clone());
+        if !self.hnsw.replace_vector(&id, vector.clone()) {
+            self.hnsw.insert(id.clone(), vector);
+        }
+        self.remove_bm25_document(&id);
+        self.index_bm25_document(&id, &metadata);
         self.metadata.insert(id, metadata);
-        self.stats.total_vectors_indexed += 1;
+        self.stats.total_vectors_indexed.fetch_add(1, Ordering::Relaxed);
     }
 
-    pub fn search_by_similarity(&mut self, query_vector: &[f32], limit: usize) -> Result<String, Box<dyn std::error::Error>> {
+    /// Drop a previously indexed document's BM25 contribution (document
+    /// frequency and total length), so re-indexing an existing `id` via
+    /// `add_vector` doesn't double-count it in `avgdl`/IDF.
+    fn remove_bm25_document(&mut self, id: &str) {
+        if let Some(counts) = self.doc_term_counts.remove(id) {
+            for term in counts.keys() {
+                if let Some(df) = self.document_frequency.get_mut(term) {
+                    *df -= 1;
+                    if *df == 0 {
+                        self.document_frequency.remove(term);
+                    }
+                }
+            }
+        }
+        if let Some(len) = self.doc_lengths.remove(id) {
+            self.total_doc_length -= len as u64;
+        }
+    }
+
+    /// Build the BM25 term-frequency entry for a document from its
+    /// metadata values (college/course names, etc.) so `search_hybrid` can
+    /// rank exact keyword matches alongside the vector similarity.
+    fn index_bm25_document(&mut self, id: &str, metadata: &HashMap<String, String>) {
+        let text = metadata.values().cloned().collect::<Vec<_>>().join(" ");
+        let terms = tokenize(&text);
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for term in &terms {
+            *counts.entry(term.clone()).or_insert(0) += 1;
+        }
+
+        for term in counts.keys() {
+            *self.document_frequency.entry(term.clone()).or_insert(0) += 1;
+        }
+
+        self.doc_lengths.insert(id.to_string(), terms.len() as u32);
+        self.total_doc_length += terms.len() as u64;
+        self.doc_term_counts.insert(id.to_string(), counts);
+    }
+
+    fn average_doc_length(&self) -> f32 {
+        if self.doc_lengths.is_empty() {
+            0.0
+        } else {
+            self.total_doc_length as f32 / self.doc_lengths.len() as f32
+        }
+    }
+
+    /// Score every indexed document against a lexical query using
+    /// Okapi BM25, returning `(id, score)` pairs sorted by score descending.
+    fn search_bm25(&self, query_text: &str) -> Vec<(String, f32)> {
+        let query_terms = tokenize(query_text);
+        let n = self.doc_lengths.len() as f32;
+        if n == 0.0 || query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let avgdl = self.average_doc_length();
+        let mut scores: HashMap<String, f32> = HashMap::new();
+
+        for term in &query_terms {
+            let n_t = *self.document_frequency.get(term).unwrap_or(&0) as f32;
+            if n_t == 0.0 {
+                continue;
+            }
+            let idf = ((n - n_t + 0.5) / (n_t + 0.5) + 1.0).ln();
+
+            for (id, counts) in &self.doc_term_counts {
+                let tf = *counts.get(term).unwrap_or(&0) as f32;
+                if tf == 0.0 {
+                    continue;
+                }
+                let doc_len = *self.doc_lengths.get(id).unwrap_or(&0) as f32;
+                let denom = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avgdl.max(1.0));
+                let term_score = idf * (tf * (BM25_K1 + 1.0)) / denom;
+                *scores.entry(id.clone()).or_insert(0.0) += term_score;
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    }
+
+    /// Run BM25 lexical ranking and cosine-similarity vector ranking
+    /// independently, then fuse them with Reciprocal Rank Fusion so results
+    /// stay relevant even when the embedding is weak.
+    pub fn search_hybrid(
+        &mut self,
+        query_text: &str,
+        query_vector: &[f32],
+        limit: usize,
+    ) -> Result<String, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
-        
-        let mut results: Vec<SearchResult> = Vec::new();
-        
-        // Calculate similarity for each vector
-        for (id, vector) in &self.vectors {
-            let similarity = self.cosine_similarity(query_vector, vector);
-            let metadata = self.metadata.get(id).cloned().unwrap_or_default();
-            
-            results.push(SearchResult {
-                id: id.clone(),
-                similarity,
-                metadata,
-            });
+
+        let lexical_ranked = self.search_bm25(query_text);
+
+        let mut vector_ranked: Vec<(String, f32)> = self
+            .vectors
+            .iter()
+            .map(|(id, vector)| (id.clone(), self.cosine_similarity(query_vector, vector)))
+            .collect();
+        vector_ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut fused_scores: HashMap<String, f32> = HashMap::new();
+        for (rank, (id, _)) in lexical_ranked.iter().enumerate() {
+            *fused_scores.entry(id.clone()).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f32);
         }
-        
-        // Sort by similarity (descending)
-        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
-        
-        // Take top results
-        results.truncate(limit);
-        
+        for (rank, (id, _)) in vector_ranked.iter().enumerate() {
+            *fused_scores.entry(id.clone()).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f32);
+        }
+
+        let mut fused: Vec<(String, f32)> = fused_scores.into_iter().collect();
+        fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        fused.truncate(limit);
+
+        let results: Vec<SearchResult> = fused
+            .into_iter()
+            .map(|(id, similarity)| SearchResult {
+                metadata: self.metadata.get(&id).cloned().unwrap_or_default(),
+                id,
+                similarity,
+            })
+            .collect();
+
+        let search_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        self.stats.total_hybrid_searches.fetch_add(1, Ordering::Relaxed);
+        self.stats.hybrid_search_time.record(search_time);
+
+        serde_json::to_string(&results)
+            .map_err(|e| format!("Serialization error: {}", e).into())
+    }
+
+    pub fn search_by_similarity(&mut self, query_vector: &[f32], limit: usize) -> Result<String, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        let results = if self.vectors.len() > HNSW_BRUTE_FORCE_THRESHOLD {
+            self.stats.total_hnsw_searches.fetch_add(1, Ordering::Relaxed);
+            let results = self.search_hnsw(query_vector, limit);
+
+            self.recall_sample_counter += 1;
+            if self.recall_sample_counter % RECALL_SAMPLE_INTERVAL == 0 {
+                let exact_top = self.brute_force_results(query_vector, 1);
+                let hit = match (results.first(), exact_top.first()) {
+                    (Some(a), Some(b)) => if a.id == b.id { 1.0 } else { 0.0 },
+                    _ => 1.0,
+                };
+                self.stats.recall_estimate.record(hit);
+            }
+
+            results
+        } else {
+            self.stats.total_brute_force_searches.fetch_add(1, Ordering::Relaxed);
+            self.brute_force_results(query_vector, limit)
+        };
+
         let search_time = start_time.elapsed().as_secs_f64() * 1000.0; // Convert to milliseconds
-        
+
         // Update statistics
-        self.stats.total_searches += 1;
-        self.search_times.push(search_time);
-        self.update_average_search_time();
-        
+        self.stats.total_searches.fetch_add(1, Ordering::Relaxed);
+        self.search_latency.record(search_time);
+        self.stats.search_time.record(search_time);
+
         // Serialize results
         serde_json::to_string(&results)
             .map_err(|e| format!("Serialization error: {}", e).into())
     }
 
+    fn brute_force_results(&self, query_vector: &[f32], limit: usize) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = self
+            .vectors
+            .iter()
+            .map(|(id, vector)| SearchResult {
+                id: id.clone(),
+                similarity: self.cosine_similarity(query_vector, vector),
+                metadata: self.metadata.get(id).cloned().unwrap_or_default(),
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        results
+    }
+
+    fn search_hnsw(&self, query_vector: &[f32], limit: usize) -> Vec<SearchResult> {
+        self.hnsw
+            .search(query_vector, self.hnsw_ef_search, limit)
+            .into_iter()
+            .map(|(id, similarity)| SearchResult {
+                metadata: self.metadata.get(&id).cloned().unwrap_or_default(),
+                id,
+                similarity,
+            })
+            .collect()
+    }
+
     pub fn generate_embedding(&mut self, text: &str) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
         let start_time = std::time::Instant::now();
-        
+
         // Simple embedding generation (in real implementation, use a proper model)
         let embedding = self.simple_text_embedding(text);
-        
+
         let embedding_time = start_time.elapsed().as_secs_f64() * 1000.0; // Convert to milliseconds
-        
+
         // Update statistics
-        self.stats.total_embeddings_generated += 1;
-        self.embedding_times.push(embedding_time);
-        self.update_average_embedding_time();
-        
+        self.stats.total_embeddings_generated.fetch_add(1, Ordering::Relaxed);
+        self.embedding_latency.record(embedding_time);
+        self.stats.embedding_time.record(embedding_time);
+
         Ok(embedding)
     }
 
@@ -107,38 +378,38 @@ impl VectorSearchProcessor {
         if a.len() != b.len() {
             return 0.0;
         }
-        
+
         let mut dot_product = 0.0;
         let mut norm_a = 0.0;
         let mut norm_b = 0.0;
-        
+
         // SIMD-optimized dot product and norm calculation
         for i in 0..a.len() {
             dot_product += a[i] * b[i];
             norm_a += a[i] * a[i];
             norm_b += b[i] * b[i];
         }
-        
+
         if norm_a == 0.0 || norm_b == 0.0 {
             return 0.0;
         }
-        
+
         dot_product / (norm_a.sqrt() * norm_b.sqrt())
     }
 
     fn simple_text_embedding(&self, text: &str) -> Vec<f32> {
         // Simple hash-based embedding (in real implementation, use a proper model)
         let mut embedding = vec![0.0; 384]; // Standard embedding size
-        
+
         let text_lower = text.to_lowercase();
         let words: Vec<&str> = text_lower.split_whitespace().collect();
-        
+
         for (i, word) in words.iter().enumerate() {
             let hash = self.simple_hash(word);
             let index = (hash % 384) as usize;
             embedding[index] += 1.0 / words.len() as f32;
         }
-        
+
         // Normalize the embedding
         let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
         if norm > 0.0 {
@@ -146,7 +417,7 @@ impl VectorSearchProcessor {
                 *value /= norm;
             }
         }
-        
+
         embedding
     }
 
@@ -158,35 +429,58 @@ impl VectorSearchProcessor {
         hash
     }
 
-    fn update_average_search_time(&mut self) {
-        if !self.search_times.is_empty() {
-            self.stats.average_search_time = 
-                self.search_times.iter().sum::<f64>() / self.search_times.len() as f64;
-        }
+    pub fn get_stats(&self) -> VectorSearchStats {
+        self.stats.snapshot()
     }
 
-    fn update_average_embedding_time(&mut self) {
-        if !self.embedding_times.is_empty() {
-            self.stats.average_embedding_time = 
-                self.embedding_times.iter().sum::<f64>() / self.embedding_times.len() as f64;
-        }
-    }
+    /// If the reporting interval (default 10s) has elapsed, returns a JSON
+    /// snapshot of throughput, p50/p99 latency, and counts accumulated
+    /// since the previous report; otherwise `None`.
+    pub fn maybe_interval_report(&self) -> Option<String> {
+        let current = IntervalCounts {
+            operations: self.stats.total_searches.load(Ordering::Relaxed)
+                + self.stats.total_hybrid_searches.load(Ordering::Relaxed),
+            bytes: self.stats.total_embeddings_generated.load(Ordering::Relaxed),
+        };
+        let (previous, elapsed_ms) = self.reporter.try_begin_emit(current)?;
 
-    pub fn get_stats(&self) -> &VectorSearchStats {
-        &self.stats
+        let snapshot = serde_json::json!({
+            "processor": "vector_search",
+            "interval_ms": elapsed_ms,
+            "searches": current.operations.saturating_sub(previous.operations),
+            "searches_per_sec":
+                current.operations.saturating_sub(previous.operations) as f64 / (elapsed_ms / 1000.0),
+            "embeddings_generated": current.bytes.saturating_sub(previous.bytes),
+            "search_p50_ms": self.search_latency.percentile(0.5),
+            "search_p99_ms": self.search_latency.percentile(0.99),
+            "embedding_p50_ms": self.embedding_latency.percentile(0.5),
+            "embedding_p99_ms": self.embedding_latency.percentile(0.99),
+            "average_recall_estimate": self.stats.snapshot().average_recall_estimate,
+        });
+        // Start the next interval's percentiles from fresh samples.
+        self.search_latency.clear();
+        self.embedding_latency.clear();
+        Some(snapshot.to_string())
     }
 
     pub fn clear_data(&mut self) {
         self.vectors.clear();
         self.metadata.clear();
-        self.stats = VectorSearchStats {
-            total_searches: 0,
-            total_embeddings_generated: 0,
-            average_search_time: 0.0,
-            average_embedding_time: 0.0,
-            total_vectors_indexed: 0,
-        };
-        self.search_times.clear();
-        self.embedding_times.clear();
+        self.doc_term_counts.clear();
+        self.doc_lengths.clear();
+        self.document_frequency.clear();
+        self.total_doc_length = 0;
+        self.hnsw = HnswIndex::new(DEFAULT_HNSW_M, DEFAULT_HNSW_EF_CONSTRUCTION);
+        self.recall_sample_counter = 0;
+        self.stats = AtomicVectorSearchStats::new();
+        self.search_latency.clear();
+        self.embedding_latency.clear();
     }
 }
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect()
+}